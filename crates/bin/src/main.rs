@@ -1,11 +1,14 @@
 use std::sync::Arc;
 
 use rt_core::{
+    bvh::BvhNode,
     camera::CameraBuilder,
     hit::HittableList,
     material::{Dielectric, Lambertian, Metal},
+    output,
+    quad::Quad,
     sphere::Sphere,
-    vec3::{Color, Point3},
+    vec3::{Color, Point3, Vector3},
 };
 
 fn main() -> anyhow::Result<()> {
@@ -17,9 +20,10 @@ fn main() -> anyhow::Result<()> {
     let material_bubble = Arc::new(Dielectric::new(1.00 / 1.50));
     let material_right = Arc::new(Metal::new(Color::new(0.8, 0.6, 0.2), 1.0));
 
-    world.add(Arc::new(Sphere::new(
-        Point3::new(0.0, -100.6, -1.0),
-        100.0,
+    world.add(Arc::new(Quad::new(
+        Point3::new(-50.0, -0.6, 49.0),
+        Vector3::new(100.0, 0.0, 0.0),
+        Vector3::new(0.0, 0.0, -100.0),
         material_ground,
     )));
     world.add(Arc::new(Sphere::new(
@@ -43,14 +47,26 @@ fn main() -> anyhow::Result<()> {
         material_right,
     )));
 
-    CameraBuilder {
+    let world = BvhNode::new(world);
+
+    let film = CameraBuilder {
         aspect_ratio: 16.0 / 9.0,
         image_width: 1920,
         samples_per_pixel: 100,
         max_depth: 50,
+        vfov: 20.0,
+        lookfrom: Point3::new(-2.0, 2.0, 1.0),
+        lookat: Point3::new(0.0, 0.0, -1.0),
+        vup: Vector3::new(0.0, 1.0, 0.0),
+        defocus_angle: 10.0,
+        focus_dist: 3.4,
+        ..CameraBuilder::default()
     }
     .build()
     .render(&world)?;
 
+    output::save(&film, "image.png")?;
+    println!("Image rendered to 'image.png'");
+
     Ok(())
 }
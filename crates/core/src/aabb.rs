@@ -0,0 +1,112 @@
+use crate::{
+    interval::{self, Interval},
+    ray::Ray,
+    vec3::Point3,
+};
+
+/// Axis-aligned bounding box.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aabb {
+    pub x: Interval,
+    pub y: Interval,
+    pub z: Interval,
+}
+
+impl Default for Aabb {
+    fn default() -> Self {
+        EMPTY
+    }
+}
+
+impl Aabb {
+    pub const fn new(x: Interval, y: Interval, z: Interval) -> Self {
+        Self { x, y, z }
+    }
+
+    /// Treats the two points as extrema of the box, in any order.
+    pub fn from_points(a: Point3, b: Point3) -> Self {
+        let span = |i: usize| Interval::new(a[i].min(b[i]), a[i].max(b[i]));
+        Self::new(span(0), span(1), span(2))
+    }
+
+    /// Creates the tightest box enclosing both `a` and `b`.
+    pub fn enclosing(a: &Aabb, b: &Aabb) -> Self {
+        Self::new(
+            Interval::enclosing(&a.x, &b.x),
+            Interval::enclosing(&a.y, &b.y),
+            Interval::enclosing(&a.z, &b.z),
+        )
+    }
+
+    /// Pads any side narrower than a small delta, so that planar primitives
+    /// still get a box with volume.
+    pub fn pad_to_minimums(&self) -> Self {
+        const DELTA: f32 = 0.0001;
+        let pad = |i: Interval| if i.size() < DELTA { i.expand(DELTA) } else { i };
+
+        Self::new(pad(self.x), pad(self.y), pad(self.z))
+    }
+
+    pub const fn axis_interval(&self, n: usize) -> &Interval {
+        match n {
+            1 => &self.y,
+            2 => &self.z,
+            _ => &self.x,
+        }
+    }
+
+    /// Returns the index of the longest axis of the box.
+    pub fn longest_axis(&self) -> usize {
+        if self.x.size() > self.y.size() {
+            if self.x.size() > self.z.size() { 0 } else { 2 }
+        } else if self.y.size() > self.z.size() {
+            1
+        } else {
+            2
+        }
+    }
+
+    pub fn centroid(&self) -> Point3 {
+        Point3::new(
+            0.5 * (self.x.min + self.x.max),
+            0.5 * (self.y.min + self.y.max),
+            0.5 * (self.z.min + self.z.max),
+        )
+    }
+
+    pub fn surface_area(&self) -> f32 {
+        let (dx, dy, dz) = (self.x.size(), self.y.size(), self.z.size());
+        if dx < 0.0 || dy < 0.0 || dz < 0.0 {
+            return 0.0;
+        }
+        2.0 * (dx * dy + dy * dz + dz * dx)
+    }
+
+    /// Slab test against the ray over the parameter range `ray_t`.
+    pub fn hit(&self, r: &Ray, ray_t: &Interval) -> bool {
+        let ray_orig = r.origin();
+        let ray_dir = r.direction();
+
+        let mut t_min = ray_t.min;
+        let mut t_max = ray_t.max;
+        for axis in 0..3 {
+            let ax = self.axis_interval(axis);
+            let adinv = 1.0 / ray_dir[axis];
+
+            let t0 = (ax.min - ray_orig[axis]) * adinv;
+            let t1 = (ax.max - ray_orig[axis]) * adinv;
+            let (t0, t1) = if t0 < t1 { (t0, t1) } else { (t1, t0) };
+
+            t_min = t_min.max(t0);
+            t_max = t_max.min(t1);
+            if t_max <= t_min {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+pub const EMPTY: Aabb = Aabb::new(interval::EMPTY, interval::EMPTY, interval::EMPTY);
+
+pub const UNIVERSE: Aabb = Aabb::new(interval::UNIVERSE, interval::UNIVERSE, interval::UNIVERSE);
@@ -0,0 +1,216 @@
+use std::sync::Arc;
+
+use crate::{
+    aabb::{self, Aabb},
+    hit::{HitRecord, Hittable, HittableList},
+    interval::Interval,
+    ray::Ray,
+    rng,
+    vec3::{Point3, Vector3},
+};
+
+/// Number of centroid buckets evaluated per axis by the SAH split.
+const SAH_BUCKETS: usize = 12;
+
+/// A node of a bounding volume hierarchy, built with the surface area heuristic.
+#[derive(Clone)]
+pub struct BvhNode {
+    left: Arc<dyn Hittable + Send + Sync + 'static>,
+    /// `None` for a leaf holding a single object, so that it is only tested once.
+    right: Option<Arc<dyn Hittable + Send + Sync + 'static>>,
+    bbox: Aabb,
+}
+
+impl BvhNode {
+    pub fn new(list: HittableList) -> Self {
+        let mut objects = list.objects;
+        Self::from_objects(&mut objects)
+    }
+
+    pub fn from_objects(objects: &mut [Arc<dyn Hittable + Send + Sync + 'static>]) -> Self {
+        let bbox = objects.iter().fold(aabb::EMPTY, |bbox, obj| {
+            Aabb::enclosing(&bbox, &obj.bounding_box())
+        });
+
+        match objects {
+            [] => Self {
+                left: Arc::new(HittableList::default()),
+                right: None,
+                bbox,
+            },
+            [only] => Self {
+                left: only.clone(),
+                right: None,
+                bbox,
+            },
+            [a, b] => Self {
+                left: a.clone(),
+                right: Some(b.clone()),
+                bbox,
+            },
+            _ => {
+                let mid = sah_partition(objects);
+                let (left, right) = objects.split_at_mut(mid);
+                Self {
+                    left: Arc::new(Self::from_objects(left)),
+                    right: Some(Arc::new(Self::from_objects(right))),
+                    bbox,
+                }
+            }
+        }
+    }
+}
+
+/// Reorders `objects` around the cheapest SAH split and returns the split index.
+fn sah_partition(objects: &mut [Arc<dyn Hittable + Send + Sync + 'static>]) -> usize {
+    let centroid_bounds = objects.iter().fold(aabb::EMPTY, |bounds, obj| {
+        let c = obj.bounding_box().centroid();
+        Aabb::enclosing(&bounds, &Aabb::from_points(c, c))
+    });
+    let axis = centroid_bounds.longest_axis();
+    let extent = *centroid_bounds.axis_interval(axis);
+
+    let centroid = |obj: &Arc<dyn Hittable + Send + Sync + 'static>| -> f32 {
+        obj.bounding_box().centroid()[axis]
+    };
+
+    // All centroids coincide: any split is as good as another.
+    if extent.size() <= 0.0 {
+        return objects.len() / 2;
+    }
+
+    let bucket_of = |c: f32| -> usize {
+        (((c - extent.min) / extent.size()) * SAH_BUCKETS as f32).min(SAH_BUCKETS as f32 - 1.0)
+            as usize
+    };
+
+    let mut counts = [0usize; SAH_BUCKETS];
+    let mut bounds = [aabb::EMPTY; SAH_BUCKETS];
+    for obj in objects.iter() {
+        let b = bucket_of(centroid(obj));
+        counts[b] += 1;
+        bounds[b] = Aabb::enclosing(&bounds[b], &obj.bounding_box());
+    }
+
+    // Cost of splitting after each bucket, relative to the traversal cost.
+    let best_split = (0..SAH_BUCKETS - 1)
+        .map(|split| {
+            let (mut lb, mut lc) = (aabb::EMPTY, 0);
+            for i in 0..=split {
+                lb = Aabb::enclosing(&lb, &bounds[i]);
+                lc += counts[i];
+            }
+            let (mut rb, mut rc) = (aabb::EMPTY, 0);
+            for i in split + 1..SAH_BUCKETS {
+                rb = Aabb::enclosing(&rb, &bounds[i]);
+                rc += counts[i];
+            }
+            let cost = lc as f32 * lb.surface_area() + rc as f32 * rb.surface_area();
+            (split, cost)
+        })
+        .filter(|&(split, _)| {
+            let left: usize = counts[..=split].iter().sum();
+            left > 0 && left < objects.len()
+        })
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(split, _)| split);
+
+    let Some(split) = best_split else {
+        objects.sort_by(|a, b| centroid(a).total_cmp(&centroid(b)));
+        return objects.len() / 2;
+    };
+
+    let mut mid = 0;
+    for i in 0..objects.len() {
+        if bucket_of(centroid(&objects[i])) <= split {
+            objects.swap(i, mid);
+            mid += 1;
+        }
+    }
+    mid
+}
+
+impl Hittable for BvhNode {
+    fn hit(&self, r: &Ray, interval: &Interval) -> Option<HitRecord> {
+        if !self.bbox.hit(r, interval) {
+            return None;
+        }
+
+        let hit_left = self.left.hit(r, interval);
+        let Some(right) = &self.right else {
+            return hit_left;
+        };
+        let max = hit_left.as_ref().map_or(interval.max, |rec| rec.t);
+        let hit_right = right.hit(r, &Interval::new(interval.min, max));
+
+        hit_right.or(hit_left)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+
+    /// Even mixture of the two children, matching [`BvhNode::random`].
+    fn pdf_value(&self, origin: &Point3, direction: &Vector3) -> f32 {
+        let left = self.left.pdf_value(origin, direction);
+        match &self.right {
+            Some(right) => 0.5 * (left + right.pdf_value(origin, direction)),
+            None => left,
+        }
+    }
+
+    fn random(&self, origin: &Point3) -> Vector3 {
+        match &self.right {
+            Some(right) if rng::random_range(0.0..1.0) >= 0.5 => right.random(origin),
+            _ => self.left.random(origin),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+    use crate::{material::Lambertian, sphere::Sphere, vec3::Color};
+
+    /// Counts how often a ray is tested against the wrapped object.
+    struct Counted {
+        object: Sphere,
+        hits: AtomicUsize,
+    }
+
+    impl Hittable for Counted {
+        fn hit(&self, r: &Ray, interval: &Interval) -> Option<HitRecord> {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            self.object.hit(r, interval)
+        }
+
+        fn bounding_box(&self) -> Aabb {
+            self.object.bounding_box()
+        }
+    }
+
+    #[test]
+    fn single_object_is_tested_once() {
+        let mat = Arc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5)));
+        let counted = Arc::new(Counted {
+            object: Sphere::new(Point3::new(0.0, 0.0, -2.0), 0.5, mat),
+            hits: AtomicUsize::new(0),
+        });
+        let bvh = BvhNode::new(HittableList::new(vec![counted.clone()]));
+
+        let r = Ray::new(Point3::new(0.0, 0.0, 0.0), Vector3::new(0.0, 0.0, -1.0));
+        let rec = bvh.hit(&r, &Interval::new(0.001, f32::INFINITY));
+        assert_eq!(rec.map(|rec| rec.t), Some(1.5));
+        assert_eq!(counted.hits.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn empty_tree_hits_nothing() {
+        let bvh = BvhNode::new(HittableList::default());
+        let r = Ray::new(Point3::new(0.0, 0.0, 0.0), Vector3::new(0.0, 0.0, -1.0));
+        assert!(bvh.hit(&r, &Interval::new(0.001, f32::INFINITY)).is_none());
+        assert_eq!(bvh.pdf_value(r.origin(), r.direction()), 0.0);
+    }
+}
@@ -1,122 +1,597 @@
 #![allow(unused)]
 
-use std::io::Write;
+use std::{
+    f32::consts::PI,
+    sync::Arc,
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+};
 
 use indicatif::{ProgressBar, ProgressStyle};
 
 use crate::{
+    film::Film,
     hit::Hittable,
-    interval::Interval,
+    integrator::{Integrator, PathTracer, RenderContext, RussianRoulette},
     ray::Ray,
-    vec3::{Color, Point3, Vector3, Wrapper},
+    rng,
+    sampler::{Sampler, SamplerKind},
+    utils::degrees_to_radians,
+    vec3::{Color, Point3, Vector3},
 };
 
+const TILE_SIZE: i32 = 32;
+
+/// Per-pixel sample budget driven by the running variance of each pixel.
+///
+/// Every pixel takes at least `min_samples`, then keeps sampling until the
+/// standard error of its gamma-encoded luminance falls to `threshold` or it
+/// reaches `max_samples`.
+///
+/// The error estimate allows for a bright contribution the pixel has not seen
+/// yet, so dark pixels need many samples to stop early: one that has only
+/// seen black may be lit through a rare path and runs to `max_samples`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AdaptiveSampling {
+    pub min_samples: u32,
+    pub max_samples: u32,
+    /// Target error in displayed units, where `1.0` is full white.
+    pub threshold: f32,
+}
+
+impl Default for AdaptiveSampling {
+    fn default() -> Self {
+        Self {
+            min_samples: 16,
+            max_samples: 1024,
+            threshold: 0.01,
+        }
+    }
+}
+
+/// A rectangular block of pixels, `[x0, x1) x [y0, y1)`.
+#[derive(Debug, Clone, Copy)]
+struct Tile {
+    x0: i32,
+    y0: i32,
+    x1: i32,
+    y1: i32,
+}
+
+/// What a ray sees when it escapes the scene.
+#[derive(Debug, Default, Clone, Copy)]
+pub enum Background {
+    /// Vertical white-to-blue sky gradient.
+    #[default]
+    Sky,
+    /// A constant color; use black for scenes lit only by emitters.
+    Solid(Color),
+}
+
+impl Background {
+    pub fn color(&self, r: &Ray) -> Color {
+        match self {
+            Self::Sky => {
+                let unit_direction = r.direction().unit_vector();
+                let a = 0.5 * (unit_direction.y() + 1.0);
+                (1.0 - a) * Color::new(1.0, 1.0, 1.0) + a * Color::new(0.5, 0.7, 1.0)
+            }
+            Self::Solid(color) => *color,
+        }
+    }
+}
+
+/// How the camera maps directions onto the image.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum Projection {
+    /// Pinhole (or thin lens) projection with the builder's `vfov`.
+    #[default]
+    Perspective,
+    /// Parallel rays along the view direction through a viewport `height`
+    /// world units tall; the width follows the aspect ratio.
+    Orthographic { height: f32 },
+    /// Equidistant fisheye whose image circle, inscribed in the shorter image
+    /// side, covers `fov` degrees. Pixels outside the circle are black.
+    Fisheye { fov: f32 },
+    /// Full 360 by 180 degree latitude-longitude panorama centered on the view
+    /// direction; use a 2:1 aspect ratio for square pixels.
+    Equirectangular,
+}
+
+/// How the two eye views are packed into one image.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StereoLayout {
+    /// Left eye in the left half, right eye in the right half.
+    SideBySide,
+    /// Left eye in the top half, right eye in the bottom half.
+    OverUnder,
+}
+
+/// Renders a view for each eye, `interocular_distance` apart along the
+/// camera's horizontal axis.
+///
+/// The perspective and orthographic projections use parallel, off-axis
+/// frustums that agree on the plane `convergence_distance` in front of the
+/// camera, so objects there appear at screen depth. A fisheye only offsets the
+/// eyes. An equirectangular projection becomes omnidirectional stereo (ODS):
+/// each column's rays start on the circle of interocular diameter, offset
+/// sideways from their longitude, and convergence is at infinity.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stereo {
+    pub layout: StereoLayout,
+    pub interocular_distance: f32,
+    pub convergence_distance: f32,
+}
+
+impl Default for Stereo {
+    fn default() -> Self {
+        Self {
+            layout: StereoLayout::SideBySide,
+            interocular_distance: 0.064,
+            convergence_distance: 10.0,
+        }
+    }
+}
+
 pub struct Camera {
     image_height: i32,
 
     pub aspect_ratio: f32,
     pub image_width: i32,
 
+    // Read-only: the viewport below is derived from them in `CameraBuilder::build`.
+    projection: Projection,
+    stereo: Option<Stereo>,
+
     center: Point3,
     pixel00_loc: Point3,
 
     pixel_delta_u: Point3,
     pixel_delta_v: Point3,
 
+    u: Vector3,
+    v: Vector3,
+    w: Vector3,
+
+    defocus_angle: f32,
+    focus_dist: f32,
+    defocus_disk_u: Vector3,
+    defocus_disk_v: Vector3,
+
+    shutter_open: f32,
+    shutter_close: f32,
+
     pub samples_per_pixel: i32,
     pixel_samples_scale: f32,
+    pub adaptive_sampling: Option<AdaptiveSampling>,
 
     pub max_depth: usize,
+    pub russian_roulette: Option<RussianRoulette>,
+
+    pub background: Background,
+    lights: Option<Arc<dyn Hittable + Send + Sync + 'static>>,
+
+    pub integrator: Arc<dyn Integrator + Send + Sync>,
+
+    pub sampler: SamplerKind,
+    pub seed: u64,
+
+    pub threads: usize,
+
+    pub progress: bool,
 }
 
 impl Camera {
-    pub fn render<H: Hittable>(&mut self, world: &H) -> anyhow::Result<()> {
-        let pb = ProgressBar::new(self.image_height as u64 * self.image_width as u64);
-        pb.set_style(
-            ProgressStyle::default_bar()
-                .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len}")?
-                .progress_chars("#>-"),
-        );
+    pub fn projection(&self) -> Projection {
+        self.projection
+    }
+
+    pub fn stereo(&self) -> Option<Stereo> {
+        self.stereo
+    }
+
+    pub fn render<H: Hittable + Sync>(&self, world: &H) -> anyhow::Result<Film> {
+        let (film, _) = self.render_counted(world)?;
+        Ok(film)
+    }
+
+    /// Renders like [`Camera::render`] and also returns a heatmap of how many
+    /// samples each pixel took, from blue (fewest) through green to red (most).
+    pub fn render_with_heatmap<H: Hittable + Sync>(
+        &self,
+        world: &H,
+    ) -> anyhow::Result<(Film, Film)> {
+        let (film, counts) = self.render_counted(world)?;
+        let heatmap = sample_heatmap(film.width(), film.height(), &counts);
+        Ok((film, heatmap))
+    }
+
+    fn render_counted<H: Hittable + Sync>(&self, world: &H) -> anyhow::Result<(Film, Vec<u32>)> {
+        let pb = if self.progress {
+            let (width, height) = self.film_size();
+            let pb = ProgressBar::new(height as u64 * width as u64);
+            pb.set_style(
+                ProgressStyle::default_bar()
+                    .template(
+                        "{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len}",
+                    )?
+                    .progress_chars("#>-"),
+            );
+            pb
+        } else {
+            ProgressBar::hidden()
+        };
 
-        let image = std::fs::OpenOptions::new()
-            .read(true)
-            .write(true)
-            .create(true)
-            .truncate(true)
-            .open("image.ppm")?;
-        let mut writer = std::io::BufWriter::new(image);
-
-        writeln!(writer, "P3")?;
-        writeln!(writer, "{} {}", self.image_width, self.image_height)?;
-        writeln!(writer, "255")?;
-
-        for j in 0..self.image_height {
-            for i in 0..self.image_width {
-                let mut pixel_color = Color::default();
-                for _ in 0..self.samples_per_pixel {
-                    let r = self.get_ray(i, j);
-                    pixel_color += Self::ray_color(&r, world, self.max_depth);
+        let rendered = self.render_tiles(world, &pb);
+        pb.finish_and_clear();
+
+        Ok(rendered)
+    }
+
+    /// Renders the image in tiles spread across `threads` workers and returns
+    /// the averaged pixel colors along with the sample count of each pixel.
+    fn render_tiles<H: Hittable + Sync>(&self, world: &H, pb: &ProgressBar) -> (Film, Vec<u32>) {
+        let tiles = self.tiles();
+        let next_tile = AtomicUsize::new(0);
+
+        let rendered = thread::scope(|s| {
+            let workers = (0..self.threads.min(tiles.len()).max(1))
+                .map(|_| {
+                    s.spawn(|| {
+                        let mut done = Vec::new();
+                        loop {
+                            let idx = next_tile.fetch_add(1, Ordering::Relaxed);
+                            let Some(tile) = tiles.get(idx) else {
+                                break;
+                            };
+                            done.push((*tile, self.render_tile(tile, world, pb)));
+                        }
+                        done
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            workers
+                .into_iter()
+                .flat_map(|worker| worker.join().expect("render worker panicked"))
+                .collect::<Vec<_>>()
+        });
+
+        let (width, height) = self.film_size();
+        let mut film = Film::new(width as usize, height as usize);
+        let mut counts = vec![0; film.pixels().len()];
+        let pixels = film.pixels_mut();
+        for (tile, samples) in rendered {
+            let tile_width = (tile.x1 - tile.x0) as usize;
+            for (row, j) in (tile.y0..tile.y1).enumerate() {
+                let start = (j * width + tile.x0) as usize;
+                let row_samples = &samples[row * tile_width..(row + 1) * tile_width];
+                for (k, &(color, count)) in row_samples.iter().enumerate() {
+                    pixels[start + k] = color;
+                    counts[start + k] = count;
                 }
-                writeln!(
-                    writer,
-                    "{}",
-                    Wrapper::new(&(pixel_color * self.pixel_samples_scale))
-                )?;
-                pb.inc(1);
             }
         }
-        writer.flush()?;
-        drop(writer);
+        (film, counts)
+    }
 
-        pb.finish_and_clear();
-        println!("Image rendered to 'image.ppm'");
+    fn render_tile<H: Hittable>(
+        &self,
+        tile: &Tile,
+        world: &H,
+        pb: &ProgressBar,
+    ) -> Vec<(Color, u32)> {
+        let ctx = RenderContext {
+            world,
+            background: &self.background,
+            lights: self.lights.as_deref().map(|lights| lights as &dyn Hittable),
+            max_depth: self.max_depth,
+            russian_roulette: self.russian_roulette,
+        };
 
-        Ok(())
+        let samples_per_pixel = match &self.adaptive_sampling {
+            Some(adaptive) => adaptive.max_samples,
+            None => self.samples_per_pixel.max(0) as u32,
+        };
+        let mut sampler = self.sampler.create(samples_per_pixel, self.seed);
+
+        let mut samples = Vec::with_capacity(((tile.x1 - tile.x0) * (tile.y1 - tile.y0)) as usize);
+        for j in tile.y0..tile.y1 {
+            for i in tile.x0..tile.x1 {
+                samples.push(match &self.adaptive_sampling {
+                    Some(adaptive) => {
+                        self.sample_pixel_adaptive(i, j, &ctx, sampler.as_mut(), adaptive)
+                    }
+                    None => self.sample_pixel(i, j, &ctx, sampler.as_mut()),
+                });
+            }
+            pb.inc((tile.x1 - tile.x0) as u64);
+        }
+        samples
     }
 
-    fn ray_color<H: Hittable>(r: &Ray, world: &H, depth: usize) -> Color {
-        if depth == 0 {
-            return Color::default();
+    fn sample_pixel(
+        &self,
+        i: i32,
+        j: i32,
+        ctx: &RenderContext,
+        sampler: &mut dyn Sampler,
+    ) -> (Color, u32) {
+        let mut pixel_color = Color::default();
+        for index in 0..self.samples_per_pixel.max(0) as u32 {
+            pixel_color += self.sample_color(i, j, index, ctx, sampler);
         }
+        (
+            pixel_color * self.pixel_samples_scale,
+            self.samples_per_pixel.max(0) as u32,
+        )
+    }
+
+    /// Traces sample `index` of pixel `(i, j)`; pixels the projection does not
+    /// cover are black.
+    fn sample_color(
+        &self,
+        i: i32,
+        j: i32,
+        index: u32,
+        ctx: &RenderContext,
+        sampler: &mut dyn Sampler,
+    ) -> Color {
+        rng::seed_pixel_sample(self.seed, i, j, index);
+        sampler.start_pixel_sample(i, j, index);
+        let (eye, i, j) = self.eye_pixel(i, j);
+        match self.get_ray(i, j, eye, sampler) {
+            Some(r) => self.integrator.ray_color(&r, ctx, sampler),
+            None => Color::default(),
+        }
+    }
 
-        if let Some(rec) = world.hit(r, &Interval::new(0.001, f32::INFINITY)) {
-            if let Some((attenuation, scattered)) = rec.mat.scatter(r, &rec) {
-                return attenuation * Self::ray_color(&scattered, world, depth - 1);
+    /// Samples until the pixel's estimated error drops below the threshold,
+    /// tracking the running luminance variance with Welford's algorithm.
+    fn sample_pixel_adaptive(
+        &self,
+        i: i32,
+        j: i32,
+        ctx: &RenderContext,
+        sampler: &mut dyn Sampler,
+        adaptive: &AdaptiveSampling,
+    ) -> (Color, u32) {
+        let max_samples = adaptive.max_samples.max(1);
+        let min_samples = adaptive.min_samples.max(2).min(max_samples);
+
+        let mut pixel_color = Color::default();
+        let mut mean = 0.0;
+        let mut m2 = 0.0;
+        let mut n = 0;
+        while n < max_samples {
+            let color = self.sample_color(i, j, n, ctx, sampler);
+            pixel_color += color;
+
+            n += 1;
+            let y = luminance(&color);
+            let delta = y - mean;
+            mean += delta / n as f32;
+            m2 += delta * (y - mean);
+
+            if n >= min_samples && n >= 2 {
+                // Samples that all agree may still have missed rare bright paths, such
+                // as light through a small emitter or a caustic. Count one extra sample
+                // a full unit of luminance away, so agreement alone never stops a pixel.
+                let variance = (m2 + 1.0) / (n - 1) as f32;
+                let std_error = (variance / n as f32).sqrt();
+                // Carry the error through the sqrt gamma curve so that it is measured
+                // in displayed units.
+                let mean = mean.max(0.0);
+                let display_error = (mean + std_error).sqrt() - mean.sqrt();
+                if display_error <= adaptive.threshold {
+                    break;
+                }
             }
-            return Color::default();
         }
+        (pixel_color / n as f32, n)
+    }
 
-        let unit_direction = r.direction().unit_vector();
-        let a = 0.5 * (unit_direction.y() + 1.0);
-        (1.0 - a) * Color::new(1.0, 1.0, 1.0) + a * Color::new(0.5, 0.7, 1.0)
+    /// Size of the rendered image, which holds both eyes in stereo.
+    fn film_size(&self) -> (i32, i32) {
+        match self.stereo.map(|stereo| stereo.layout) {
+            None => (self.image_width, self.image_height),
+            Some(StereoLayout::SideBySide) => (2 * self.image_width, self.image_height),
+            Some(StereoLayout::OverUnder) => (self.image_width, 2 * self.image_height),
+        }
     }
 
-    fn get_ray(&self, i: i32, j: i32) -> Ray {
-        let offset = sample_square();
-        let pixel_sample = self.pixel00_loc
-            + (i as f32 + offset.x()) * self.pixel_delta_u
-            + (j as f32 + offset.y()) * self.pixel_delta_v;
+    /// Maps a film pixel to the signed offset of its eye along `u` and the
+    /// pixel within that eye's view.
+    fn eye_pixel(&self, i: i32, j: i32) -> (f32, i32, i32) {
+        let Some(stereo) = &self.stereo else {
+            return (0.0, i, j);
+        };
+
+        let half = stereo.interocular_distance / 2.0;
+        match stereo.layout {
+            StereoLayout::SideBySide if i >= self.image_width => (half, i - self.image_width, j),
+            StereoLayout::OverUnder if j >= self.image_height => (half, i, j - self.image_height),
+            _ => (-half, i, j),
+        }
+    }
+
+    fn tiles(&self) -> Vec<Tile> {
+        let (width, height) = self.film_size();
+        let mut tiles = Vec::new();
+        for y0 in (0..height).step_by(TILE_SIZE as usize) {
+            for x0 in (0..width).step_by(TILE_SIZE as usize) {
+                tiles.push(Tile {
+                    x0,
+                    y0,
+                    x1: (x0 + TILE_SIZE).min(width),
+                    y1: (y0 + TILE_SIZE).min(height),
+                });
+            }
+        }
+        tiles
+    }
+
+    /// Returns a ray through a random point of pixel `(i, j)` as seen from an
+    /// eye `eye` units along `u`, or `None` if the projection maps that point
+    /// outside its field of view.
+    fn get_ray(&self, i: i32, j: i32, eye: f32, sampler: &mut dyn Sampler) -> Option<Ray> {
+        let offset = sample_square(sampler.get_2d());
+        let (fx, fy) = (i as f32 + 0.5 + offset.x(), j as f32 + 0.5 + offset.y());
+
+        let (ray_origin, ray_direction) = match self.projection {
+            Projection::Perspective | Projection::Orthographic { .. } => {
+                let pixel_sample = self.pixel00_loc
+                    + (fx - 0.5) * self.pixel_delta_u
+                    + (fy - 0.5) * self.pixel_delta_v;
+                // Orthographic rays start on the camera plane straight behind their pixel.
+                let lens_center = match self.projection {
+                    Projection::Orthographic { .. } => pixel_sample + self.focus_dist * self.w,
+                    _ => self.center,
+                };
+
+                // An offset eye keeps its image plane and aims where the centered ray
+                // crosses the convergence plane; find that ray's point in focus.
+                let eye_offset = eye * self.u;
+                let focus_point = match &self.stereo {
+                    Some(stereo) => {
+                        pixel_sample
+                            + eye_offset * (1.0 - self.focus_dist / stereo.convergence_distance)
+                    }
+                    None => pixel_sample,
+                };
+
+                let ray_origin = if self.defocus_angle <= 0.0 {
+                    lens_center + eye_offset
+                } else {
+                    lens_center + eye_offset + self.defocus_disk_sample(sampler.get_2d())
+                };
+                (ray_origin, focus_point - ray_origin)
+            }
+            Projection::Fisheye { fov } => {
+                let radius = 0.5 * self.image_width.min(self.image_height) as f32;
+                let x = (fx - 0.5 * self.image_width as f32) / radius;
+                let y = (0.5 * self.image_height as f32 - fy) / radius;
+                let r = (x * x + y * y).sqrt();
+                if r > 1.0 {
+                    return None;
+                }
+
+                // Equidistant: the angle from the view axis grows linearly with r.
+                let theta = r * degrees_to_radians(fov) / 2.0;
+                let phi = y.atan2(x);
+                let direction =
+                    theta.sin() * (phi.cos() * self.u + phi.sin() * self.v) - theta.cos() * self.w;
+                (self.center + eye * self.u, direction)
+            }
+            Projection::Equirectangular => {
+                let longitude = (fx / self.image_width as f32 - 0.5) * 2.0 * PI;
+                let latitude = (0.5 - fy / self.image_height as f32) * PI;
+                let direction = latitude.cos()
+                    * (longitude.sin() * self.u - longitude.cos() * self.w)
+                    + latitude.sin() * self.v;
+                // ODS: the eye sits to the side of the column's horizontal view direction.
+                let right = longitude.cos() * self.u + longitude.sin() * self.w;
+                (self.center + eye * right, direction)
+            }
+        };
+
+        let ray_time = if self.shutter_close > self.shutter_open {
+            self.shutter_open + sampler.get_1d() * (self.shutter_close - self.shutter_open)
+        } else {
+            self.shutter_open
+        };
 
-        let ray_origin = self.center;
-        let ray_direction = pixel_sample - ray_origin;
+        Some(Ray::with_time(ray_origin, ray_direction, ray_time))
+    }
 
-        Ray::new(ray_origin, ray_direction)
+    /// Maps a point of `[0, 1)^2` onto the defocus disk, as an offset from the lens center.
+    fn defocus_disk_sample(&self, u: (f32, f32)) -> Vector3 {
+        let p = Vector3::sample_in_unit_disk(u);
+        p[0] * self.defocus_disk_u + p[1] * self.defocus_disk_v
     }
 }
 
-fn sample_square() -> Vector3 {
-    Vector3::new(
-        rand::random_range(-0.5..=0.5),
-        rand::random_range(-0.5..=0.5),
-        0.0,
-    )
+fn luminance(c: &Color) -> f32 {
+    0.2126 * c.x() + 0.7152 * c.y() + 0.0722 * c.z()
+}
+
+/// Maps per-pixel sample counts onto a blue-green-red ramp scaled to the
+/// largest count.
+fn sample_heatmap(width: usize, height: usize, counts: &[u32]) -> Film {
+    let most = counts.iter().copied().max().unwrap_or(0).max(1) as f32;
+    let pixels = counts
+        .iter()
+        .map(|&count| {
+            let t = count as f32 / most;
+            if t < 0.5 {
+                Color::new(0.0, 2.0 * t, 1.0 - 2.0 * t)
+            } else {
+                Color::new(2.0 * t - 1.0, 2.0 - 2.0 * t, 0.0)
+            }
+        })
+        .collect();
+    Film::from_pixels(width, height, pixels)
+}
+
+/// Maps a point of `[0, 1)^2` onto the unit square centered on the origin.
+fn sample_square(u: (f32, f32)) -> Vector3 {
+    Vector3::new(u.0 - 0.5, u.1 - 0.5, 0.0)
 }
 
 pub struct CameraBuilder {
     pub aspect_ratio: f32,
     pub image_width: i32,
     pub samples_per_pixel: i32,
+    /// Replaces the fixed `samples_per_pixel` with a variance-driven budget.
+    pub adaptive_sampling: Option<AdaptiveSampling>,
     pub max_depth: usize,
+    /// Randomly ends dim paths once they pass `start_depth` bounces; `max_depth`
+    /// still caps every path. `None` disables it.
+    pub russian_roulette: Option<RussianRoulette>,
+
+    pub projection: Projection,
+    /// Render a view per eye into one image. `image_width` and `aspect_ratio`
+    /// describe each eye, so the image doubles in width or height.
+    pub stereo: Option<Stereo>,
+    /// Vertical view angle (field of view) in degrees, for `Projection::Perspective`.
+    pub vfov: f32,
+    pub lookfrom: Point3,
+    pub lookat: Point3,
+    /// Camera-relative "up" direction.
+    pub vup: Vector3,
+
+    /// Variation angle of rays through each pixel, in degrees. Only the
+    /// perspective and orthographic projections model a lens.
+    pub defocus_angle: f32,
+    /// Distance from `lookfrom` to the plane of perfect focus.
+    pub focus_dist: f32,
+
+    /// Time at which the shutter opens; each ray gets a time in `[shutter_open, shutter_close)`.
+    pub shutter_open: f32,
+    pub shutter_close: f32,
+
+    pub background: Background,
+    /// Emitters to sample directly at every diffuse bounce (next event estimation).
+    ///
+    /// They must also be part of the world; `None` disables light sampling.
+    /// Put several emitters in a `HittableList` (or `BvhNode`), which picks one
+    /// per sample. Every emitter must implement `Hittable::pdf_value` and
+    /// `Hittable::random`, as spheres, quads, disks, triangles and triangle
+    /// meshes do, directly or through an `Instance`; other objects contribute
+    /// nothing.
+    pub lights: Option<Arc<dyn Hittable + Send + Sync + 'static>>,
+    /// Algorithm that turns camera rays into colors; defaults to `PathTracer`.
+    pub integrator: Arc<dyn Integrator + Send + Sync>,
+    /// Sample pattern for pixel positions, lens, time and scattering decisions.
+    pub sampler: SamplerKind,
+    /// Seed for every random choice made while rendering. The same scene and
+    /// seed give a bit-identical image for any number of threads.
+    pub seed: u64,
+
+    /// Number of render threads; `0` uses all available cores.
+    pub threads: usize,
+    /// Show a progress bar on the terminal while rendering.
+    pub progress: bool,
 }
 
 impl Default for CameraBuilder {
@@ -125,7 +600,26 @@ impl Default for CameraBuilder {
             aspect_ratio: 16.0 / 9.0,
             image_width: 400,
             samples_per_pixel: 10,
+            adaptive_sampling: None,
             max_depth: 10,
+            russian_roulette: Some(RussianRoulette::default()),
+            projection: Projection::Perspective,
+            stereo: None,
+            vfov: 90.0,
+            lookfrom: Point3::new(0.0, 0.0, 0.0),
+            lookat: Point3::new(0.0, 0.0, -1.0),
+            vup: Vector3::new(0.0, 1.0, 0.0),
+            defocus_angle: 0.0,
+            focus_dist: 10.0,
+            shutter_open: 0.0,
+            shutter_close: 1.0,
+            background: Background::Sky,
+            lights: None,
+            integrator: Arc::new(PathTracer),
+            sampler: SamplerKind::Independent,
+            seed: 0,
+            threads: 0,
+            progress: true,
         }
     }
 }
@@ -145,35 +639,319 @@ impl CameraBuilder {
 
         let pixel_samples_scale = 1.0 / self.samples_per_pixel as f32;
 
-        let center = Point3::new(0.0, 0.0, 0.0);
+        let threads = match self.threads {
+            0 => thread::available_parallelism().map_or(1, |n| n.get()),
+            n => n,
+        };
+
+        let center = self.lookfrom;
 
-        let focal_length = 1.0;
-        let viewport_height = 2.0;
+        let viewport_height = match self.projection {
+            Projection::Orthographic { height } => height,
+            _ => {
+                let theta = degrees_to_radians(self.vfov);
+                let h = (theta / 2.0).tan();
+                2.0 * h * self.focus_dist
+            }
+        };
         let viewport_width = viewport_height * (image_width as f32 / image_height as f32);
 
-        let viewport_u = Vector3::new(viewport_width, 0.0, 0.0);
-        let viewport_v = Vector3::new(0.0, -viewport_height, 0.0);
+        // Orthonormal basis for the camera coordinate frame.
+        let w = (self.lookfrom - self.lookat).unit_vector();
+        let u = self.vup.cross(&w).unit_vector();
+        let v = w.cross(&u);
+
+        let viewport_u = viewport_width * u;
+        let viewport_v = viewport_height * -v;
 
         let pixel_delta_u = viewport_u / image_width as f32;
         let pixel_delta_v = viewport_v / image_height as f32;
 
         let viewport_upper_left =
-            center - Vector3::new(0.0, 0.0, focal_length) - viewport_u / 2.0 - viewport_v / 2.0;
+            center - self.focus_dist * w - viewport_u / 2.0 - viewport_v / 2.0;
         let pixel00_loc = viewport_upper_left + 0.5 * (pixel_delta_u + pixel_delta_v);
 
+        let defocus_radius = self.focus_dist * degrees_to_radians(self.defocus_angle / 2.0).tan();
+        let defocus_disk_u = u * defocus_radius;
+        let defocus_disk_v = v * defocus_radius;
+
         Camera {
             image_height,
             aspect_ratio,
             image_width,
+            projection: self.projection,
+            stereo: self.stereo,
             center,
             pixel00_loc,
             pixel_delta_u,
             pixel_delta_v,
 
+            u,
+            v,
+            w,
+
+            defocus_angle: self.defocus_angle,
+            focus_dist: self.focus_dist,
+            defocus_disk_u,
+            defocus_disk_v,
+
+            shutter_open: self.shutter_open,
+            shutter_close: self.shutter_close,
+
             samples_per_pixel: self.samples_per_pixel,
             pixel_samples_scale,
+            adaptive_sampling: self.adaptive_sampling,
 
             max_depth: self.max_depth,
+            russian_roulette: self.russian_roulette,
+
+            background: self.background,
+            lights: self.lights,
+
+            integrator: self.integrator,
+            sampler: self.sampler,
+            seed: self.seed,
+
+            threads,
+
+            progress: self.progress,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        hit::HittableList,
+        material::{DiffuseLight, Lambertian},
+        quad::Quad,
+        sphere::Sphere,
+    };
+
+    fn small_world() -> HittableList {
+        let mut world = HittableList::default();
+        let ground = Arc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5)));
+        let ball = Arc::new(Lambertian::new(Color::new(0.7, 0.3, 0.2)));
+        world.add(Arc::new(Sphere::new(
+            Point3::new(0.0, -100.5, -1.0),
+            100.0,
+            ground,
+        )));
+        world.add(Arc::new(Sphere::new(
+            Point3::new(0.0, 0.0, -1.0),
+            0.5,
+            ball,
+        )));
+        world
+    }
+
+    fn small_camera() -> CameraBuilder {
+        CameraBuilder {
+            image_width: 16,
+            samples_per_pixel: 4,
+            max_depth: 5,
+            progress: false,
+            ..CameraBuilder::default()
+        }
+    }
+
+    #[test]
+    fn adaptive_sampling_with_a_single_sample_budget() {
+        let camera = CameraBuilder {
+            adaptive_sampling: Some(AdaptiveSampling {
+                min_samples: 1,
+                max_samples: 1,
+                threshold: 0.01,
+            }),
+            ..small_camera()
+        }
+        .build();
+
+        // Every pixel takes exactly one sample, so the heatmap is uniform.
+        let (_, heatmap) = camera.render_with_heatmap(&small_world()).unwrap();
+        let first = heatmap.pixels()[0];
+        assert!(heatmap.pixels().iter().all(|&c| c == first));
+    }
+
+    /// A floor lit only by a small emitter that no camera ray sees directly,
+    /// so most paths return black and a few return a bright contribution.
+    fn rarely_lit_world() -> HittableList {
+        let mut world = HittableList::default();
+        let floor = Arc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5)));
+        let light = Arc::new(DiffuseLight::new(Color::new(50.0, 50.0, 50.0)));
+        world.add(Arc::new(Quad::new(
+            Point3::new(-2.0, 0.0, -2.0),
+            Vector3::new(4.0, 0.0, 0.0),
+            Vector3::new(0.0, 0.0, 4.0),
+            floor,
+        )));
+        world.add(Arc::new(Quad::new(
+            Point3::new(-0.2, 1.0, -0.2),
+            Vector3::new(0.4, 0.0, 0.0),
+            Vector3::new(0.0, 0.0, 0.4),
+            light,
+        )));
+        world
+    }
+
+    fn mean_luminance(film: &Film) -> f32 {
+        film.pixels().iter().map(luminance).sum::<f32>() / film.pixels().len() as f32
+    }
+
+    #[test]
+    fn adaptive_sampling_keeps_sampling_rarely_lit_pixels() {
+        let world = rarely_lit_world();
+        let camera = |adaptive_sampling| {
+            CameraBuilder {
+                aspect_ratio: 1.0,
+                image_width: 8,
+                samples_per_pixel: 1024,
+                adaptive_sampling,
+                max_depth: 3,
+                russian_roulette: None,
+                vfov: 40.0,
+                lookfrom: Point3::new(0.0, 0.5, 3.0),
+                lookat: Point3::new(0.0, 0.0, 0.0),
+                background: Background::Solid(Color::default()),
+                ..small_camera()
+            }
+            .build()
+        };
+
+        let fixed = mean_luminance(&camera(None).render(&world).unwrap());
+        let adaptive = mean_luminance(
+            &camera(Some(AdaptiveSampling::default()))
+                .render(&world)
+                .unwrap(),
+        );
+        assert!(fixed > 0.0);
+        assert!(
+            (adaptive - fixed).abs() < 0.1 * fixed,
+            "adaptive {} vs fixed {}",
+            adaptive,
+            fixed
+        );
+    }
+
+    /// Puts every sample in the middle of its domain.
+    struct CenterSampler;
+
+    impl Sampler for CenterSampler {
+        fn start_pixel_sample(&mut self, _x: i32, _y: i32, _index: u32) {}
+
+        fn get_1d(&mut self) -> f32 {
+            0.5
+        }
+
+        fn get_2d(&mut self) -> (f32, f32) {
+            (0.5, 0.5)
+        }
+    }
+
+    fn ray_through(camera: &Camera, x: i32, y: i32) -> Option<Ray> {
+        let (eye, i, j) = camera.eye_pixel(x, y);
+        camera.get_ray(i, j, eye, &mut CenterSampler)
+    }
+
+    fn assert_close(a: &Vector3, b: &Vector3) {
+        assert!((a - b).length() < 1e-5, "{:?} vs {:?}", a, b);
+    }
+
+    fn projection_camera(projection: Projection, stereo: Option<Stereo>) -> Camera {
+        CameraBuilder {
+            aspect_ratio: 1.0,
+            image_width: 9,
+            projection,
+            stereo,
+            lookfrom: Point3::new(1.0, 2.0, 3.0),
+            lookat: Point3::new(0.0, 0.5, 0.0),
+            focus_dist: 4.0,
+            ..small_camera()
+        }
+        .build()
+    }
+
+    #[test]
+    fn center_pixel_looks_along_the_view_direction() {
+        let forward = (Point3::new(0.0, 0.5, 0.0) - Point3::new(1.0, 2.0, 3.0)).unit_vector();
+        for projection in [
+            Projection::Perspective,
+            Projection::Orthographic { height: 2.0 },
+            Projection::Fisheye { fov: 180.0 },
+            Projection::Equirectangular,
+        ] {
+            let camera = projection_camera(projection, None);
+            assert_eq!(camera.projection(), projection);
+
+            let r = ray_through(&camera, 4, 4).unwrap();
+            assert_close(&r.direction().unit_vector(), &forward);
+            assert_close(r.origin(), &Point3::new(1.0, 2.0, 3.0));
+        }
+
+        // Orthographic rays are parallel; fisheye corners fall outside the image circle.
+        let ortho = projection_camera(Projection::Orthographic { height: 2.0 }, None);
+        assert_close(
+            &ray_through(&ortho, 0, 8).unwrap().direction().unit_vector(),
+            &forward,
+        );
+        let fisheye = projection_camera(Projection::Fisheye { fov: 180.0 }, None);
+        assert!(ray_through(&fisheye, 0, 0).is_none());
+    }
+
+    #[test]
+    fn ods_rays_start_on_the_interocular_circle() {
+        let stereo = Stereo {
+            layout: StereoLayout::SideBySide,
+            interocular_distance: 0.5,
+            ..Stereo::default()
+        };
+        let camera = projection_camera(Projection::Equirectangular, Some(stereo));
+        assert_eq!(camera.stereo(), Some(stereo));
+        let center = Point3::new(1.0, 2.0, 3.0);
+
+        for y in [0, 4, 7] {
+            for x in [0, 2, 4, 6, 8] {
+                let left = ray_through(&camera, x, y).unwrap();
+                let right = ray_through(&camera, x + 9, y).unwrap();
+                for r in [&left, &right] {
+                    let offset = r.origin() - center;
+                    assert!((offset.length() - 0.25).abs() < 1e-5);
+                    // The eyes sit in the horizontal plane, sideways to the view.
+                    assert!(offset.dot(&camera.v).abs() < 1e-5);
+                    assert!(offset.dot(r.direction()).abs() < 1e-5);
+                }
+                assert_close(&(left.origin() - center), &(center - right.origin()));
+                assert_close(left.direction(), right.direction());
+            }
+        }
+    }
+
+    #[test]
+    fn seeded_renders_do_not_depend_on_the_thread_count() {
+        let world = small_world();
+        let render = |sampler, threads| {
+            let camera = CameraBuilder {
+                sampler,
+                seed: 7,
+                threads,
+                ..small_camera()
+            }
+            .build();
+            let film = camera.render(&world).unwrap();
+            film.pixels()
+                .iter()
+                .flat_map(|c| [c.x().to_bits(), c.y().to_bits(), c.z().to_bits()])
+                .collect::<Vec<_>>()
+        };
+
+        for sampler in [
+            SamplerKind::Independent,
+            SamplerKind::Stratified,
+            SamplerKind::Halton,
+            SamplerKind::Sobol,
+        ] {
+            assert_eq!(render(sampler, 1), render(sampler, 4), "{:?}", sampler);
         }
     }
 }
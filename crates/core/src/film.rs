@@ -0,0 +1,65 @@
+use crate::vec3::Color;
+
+/// An in-memory image of linear radiance values, stored in row-major order.
+#[derive(Debug, Clone)]
+pub struct Film {
+    width: usize,
+    height: usize,
+    pixels: Vec<Color>,
+}
+
+impl Film {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![Color::default(); width * height],
+        }
+    }
+
+    pub fn from_pixels(width: usize, height: usize, pixels: Vec<Color>) -> Self {
+        assert_eq!(
+            pixels.len(),
+            width * height,
+            "pixel count does not match film dimensions"
+        );
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    pub const fn width(&self) -> usize {
+        self.width
+    }
+
+    pub const fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn pixels(&self) -> &[Color] {
+        &self.pixels
+    }
+
+    pub fn pixels_mut(&mut self) -> &mut [Color] {
+        &mut self.pixels
+    }
+
+    pub fn into_pixels(self) -> Vec<Color> {
+        self.pixels
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> &Color {
+        &self.pixels[y * self.width + x]
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, color: Color) {
+        self.pixels[y * self.width + x] = color;
+    }
+
+    /// Iterates over the rows of the image, top to bottom.
+    pub fn rows(&self) -> impl DoubleEndedIterator<Item = &[Color]> {
+        self.pixels.chunks(self.width.max(1))
+    }
+}
@@ -1,36 +1,72 @@
 use std::sync::Arc;
 
 use crate::{
+    aabb::{self, Aabb},
     interval::Interval,
     material::Material,
     ray::Ray,
+    rng,
     vec3::{Point3, Vector3},
 };
 
 #[derive(Clone)]
 pub struct HitRecord {
     pub p: Point3,
+    /// Shading normal, on the same side of the surface as the incoming ray.
     pub normal: Vector3,
+    /// True surface normal, on the same side of the surface as the incoming ray.
+    pub geometric_normal: Vector3,
     pub t: f32,
 
+    /// Surface coordinates of the hit point.
+    pub u: f32,
+    pub v: f32,
+    /// Partial derivatives of the surface position with respect to `u` and `v`.
+    pub dpdu: Vector3,
+    pub dpdv: Vector3,
+
     pub front_face: bool,
 
     pub mat: Arc<dyn Material + 'static>,
 }
 
 impl HitRecord {
+    /// Sets both normals from the geometric `outward_normal`, which must be a unit vector.
     pub fn set_face_normal(&mut self, r: &Ray, outward_normal: &Vector3) {
         self.front_face = r.direction().dot(outward_normal) < 0.0;
-        self.normal = if self.front_face {
+        self.geometric_normal = if self.front_face {
             *outward_normal
         } else {
             -(*outward_normal)
         };
+        self.normal = self.geometric_normal;
+    }
+
+    /// Overrides the shading normal, flipping it to the side of the geometric normal.
+    pub fn set_shading_normal(&mut self, shading_normal: &Vector3) {
+        self.normal = if shading_normal.dot(&self.geometric_normal) < 0.0 {
+            -(*shading_normal)
+        } else {
+            *shading_normal
+        };
     }
 }
 
 pub trait Hittable {
     fn hit(&self, r: &Ray, interval: &Interval) -> Option<HitRecord>;
+
+    fn bounding_box(&self) -> Aabb;
+
+    /// Density, with respect to solid angle, of [`Hittable::random`] choosing
+    /// `direction` from `origin`. Zero for objects that cannot be sampled.
+    fn pdf_value(&self, _origin: &Point3, _direction: &Vector3) -> f32 {
+        0.0
+    }
+
+    /// Return a random direction from `origin` towards the object.
+    fn random(&self, _origin: &Point3) -> Vector3 {
+        Vector3::new(1.0, 0.0, 0.0)
+    }
 }
 
 #[derive(Default, Clone)]
@@ -62,4 +98,78 @@ impl Hittable for HittableList {
 
         res
     }
+
+    fn bounding_box(&self) -> Aabb {
+        self.objects.iter().fold(aabb::EMPTY, |bbox, obj| {
+            Aabb::enclosing(&bbox, &obj.bounding_box())
+        })
+    }
+
+    /// Average of the objects' densities, matching [`HittableList::random`]'s uniform pick.
+    fn pdf_value(&self, origin: &Point3, direction: &Vector3) -> f32 {
+        if self.objects.is_empty() {
+            return 0.0;
+        }
+
+        let sum: f32 = self
+            .objects
+            .iter()
+            .map(|obj| obj.pdf_value(origin, direction))
+            .sum();
+        sum / self.objects.len() as f32
+    }
+
+    /// Samples a direction towards one object chosen uniformly at random.
+    fn random(&self, origin: &Point3) -> Vector3 {
+        if self.objects.is_empty() {
+            return Vector3::new(1.0, 0.0, 0.0);
+        }
+
+        let idx = rng::random_range(0..self.objects.len());
+        self.objects[idx].random(origin)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{material::DiffuseLight, quad::Quad, vec3::Color};
+
+    fn light_quad(corner: Point3) -> Arc<dyn Hittable + Send + Sync> {
+        let light = Arc::new(DiffuseLight::new(Color::new(1.0, 1.0, 1.0)));
+        Arc::new(Quad::new(
+            corner,
+            Vector3::new(1.0, 0.0, 0.0),
+            Vector3::new(0.0, 0.0, 1.0),
+            light,
+        ))
+    }
+
+    #[test]
+    fn list_pdf_averages_its_objects() {
+        let a = light_quad(Point3::new(-0.5, 2.0, -0.5));
+        let b = light_quad(Point3::new(5.0, 2.0, -0.5));
+        let list = HittableList::new(vec![a.clone(), b]);
+
+        let origin = Point3::new(0.0, 0.0, 0.0);
+        let towards_a = Vector3::new(0.0, 1.0, 0.0);
+        let expected = a.pdf_value(&origin, &towards_a) / 2.0;
+        assert!(expected > 0.0);
+        assert_eq!(list.pdf_value(&origin, &towards_a), expected);
+        assert_eq!(HittableList::default().pdf_value(&origin, &towards_a), 0.0);
+    }
+
+    #[test]
+    fn list_random_samples_its_objects() {
+        let list = HittableList::new(vec![
+            light_quad(Point3::new(-0.5, 2.0, -0.5)),
+            light_quad(Point3::new(5.0, 2.0, -0.5)),
+        ]);
+
+        let origin = Point3::new(0.0, 0.0, 0.0);
+        for _ in 0..100 {
+            let direction = list.random(&origin);
+            assert!(list.pdf_value(&origin, &direction) > 0.0);
+        }
+    }
 }
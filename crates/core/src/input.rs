@@ -0,0 +1,191 @@
+use std::{fs, path::Path};
+
+use anyhow::{Context, bail, ensure};
+
+use crate::{film::Film, utils::gamma_to_linear, vec3::Color};
+
+/// Reads an image file into a [`Film`] of linear color values.
+///
+/// Supports PPM (`P3`/`P6`) and PFM. 8/16-bit data is assumed to be
+/// gamma-encoded like the output of [`crate::output::PpmWriter`].
+pub fn load<P: AsRef<Path>>(path: P) -> anyhow::Result<Film> {
+    let path = path.as_ref();
+    let data = fs::read(path).with_context(|| format!("failed to read '{}'", path.display()))?;
+
+    match data.get(..2) {
+        Some(b"P3") | Some(b"P6") => read_ppm(&data),
+        Some(b"PF") => read_pfm(&data),
+        _ => bail!("'{}' is not a PPM or PFM image", path.display()),
+    }
+    .with_context(|| format!("failed to decode '{}'", path.display()))
+}
+
+/// Splits off the next whitespace-separated header token, skipping comments.
+fn next_token<'a>(data: &'a [u8], pos: &mut usize) -> anyhow::Result<&'a str> {
+    loop {
+        while data.get(*pos).is_some_and(u8::is_ascii_whitespace) {
+            *pos += 1;
+        }
+        if data.get(*pos) == Some(&b'#') {
+            while data.get(*pos).is_some_and(|&b| b != b'\n') {
+                *pos += 1;
+            }
+            continue;
+        }
+        break;
+    }
+
+    let start = *pos;
+    while data.get(*pos).is_some_and(|b| !b.is_ascii_whitespace()) {
+        *pos += 1;
+    }
+    ensure!(start < *pos, "unexpected end of header");
+    Ok(std::str::from_utf8(&data[start..*pos])?)
+}
+
+/// Returns `data[pos..pos + len]`, rejecting ranges past the end of the data.
+fn raster(data: &[u8], pos: usize, len: usize) -> anyhow::Result<&[u8]> {
+    pos.checked_add(len)
+        .and_then(|end| data.get(pos..end))
+        .context("truncated pixel data")
+}
+
+/// Number of values in a `width` by `height` image with `channels` per pixel.
+fn value_count(width: usize, height: usize, channels: usize) -> anyhow::Result<usize> {
+    width
+        .checked_mul(height)
+        .and_then(|n| n.checked_mul(channels))
+        .with_context(|| format!("image size {}x{} is too large", width, height))
+}
+
+fn read_ppm(data: &[u8]) -> anyhow::Result<Film> {
+    let mut pos = 0;
+    let magic = next_token(data, &mut pos)?;
+    let width: usize = next_token(data, &mut pos)?.parse()?;
+    let height: usize = next_token(data, &mut pos)?.parse()?;
+    let max_value: u32 = next_token(data, &mut pos)?.parse()?;
+    ensure!(
+        (1..=65535).contains(&max_value),
+        "invalid maximum value {}",
+        max_value
+    );
+
+    let count = value_count(width, height, 3)?;
+    let samples = if magic == "P3" {
+        (0..count)
+            .map(|_| Ok(next_token(data, &mut pos)?.parse::<u32>()?))
+            .collect::<anyhow::Result<Vec<_>>>()?
+    } else {
+        // Exactly one whitespace byte separates the header from the raster.
+        pos += 1;
+        let bytes_per_sample = if max_value < 256 { 1 } else { 2 };
+        let len = value_count(width, height, 3 * bytes_per_sample)?;
+        let raster = raster(data, pos, len)?;
+        if bytes_per_sample == 1 {
+            raster.iter().map(|&b| b as u32).collect()
+        } else {
+            raster
+                .chunks_exact(2)
+                .map(|b| u16::from_be_bytes([b[0], b[1]]) as u32)
+                .collect()
+        }
+    };
+
+    let scale = 1.0 / max_value as f32;
+    let pixels = samples
+        .chunks_exact(3)
+        .map(|c| {
+            Color::new(
+                gamma_to_linear(c[0] as f32 * scale),
+                gamma_to_linear(c[1] as f32 * scale),
+                gamma_to_linear(c[2] as f32 * scale),
+            )
+        })
+        .collect();
+    Ok(Film::from_pixels(width, height, pixels))
+}
+
+fn read_pfm(data: &[u8]) -> anyhow::Result<Film> {
+    let mut pos = 0;
+    let magic = next_token(data, &mut pos)?;
+    ensure!(magic == "PF", "only color PFM images are supported");
+    let width: usize = next_token(data, &mut pos)?.parse()?;
+    let height: usize = next_token(data, &mut pos)?.parse()?;
+    let scale: f32 = next_token(data, &mut pos)?.parse()?;
+    pos += 1;
+
+    let raster = raster(data, pos, value_count(width, height, 12)?)?;
+    let read = |b: &[u8]| {
+        let bytes = [b[0], b[1], b[2], b[3]];
+        if scale < 0.0 {
+            f32::from_le_bytes(bytes)
+        } else {
+            f32::from_be_bytes(bytes)
+        }
+    };
+
+    let mut film = Film::new(width, height);
+    for (i, c) in raster.chunks_exact(12).enumerate() {
+        // PFM scanlines are stored bottom to top.
+        let (x, y) = (i % width, height - 1 - i / width);
+        film.set(
+            x,
+            y,
+            Color::new(read(&c[0..4]), read(&c[4..8]), read(&c[8..12])),
+        );
+    }
+    Ok(film)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(result: anyhow::Result<Film>) -> String {
+        match result {
+            Ok(_) => panic!("expected a decode error"),
+            Err(e) => e.to_string(),
+        }
+    }
+
+    #[test]
+    fn reads_binary_ppm() {
+        let film = read_ppm(b"P6\n2 1\n255\n\xff\x00\x00\x00\x00\xff").unwrap();
+        assert_eq!((film.width(), film.height()), (2, 1));
+        assert_eq!(*film.get(0, 0), Color::new(1.0, 0.0, 0.0));
+        assert_eq!(*film.get(1, 0), Color::new(0.0, 0.0, 1.0));
+    }
+
+    #[test]
+    fn truncated_raster_is_an_error() {
+        assert_eq!(
+            error(read_ppm(b"P6\n2 2\n255\n\x00\x00\x00")),
+            "truncated pixel data"
+        );
+        assert_eq!(
+            error(read_pfm(b"PF\n1 1\n-1.0\n\x00\x00\x80\x3f")),
+            "truncated pixel data"
+        );
+        assert!(read_ppm(b"P3\n1 1\n255\n1 2").is_err());
+    }
+
+    #[test]
+    fn oversized_header_is_an_error() {
+        assert_eq!(
+            error(read_ppm(b"P6\n4294967296 4294967297 255\n\x00")),
+            "image size 4294967296x4294967297 is too large"
+        );
+        assert_eq!(
+            error(read_ppm(b"P3\n18446744073709551615 2 255\n")),
+            "image size 18446744073709551615x2 is too large"
+        );
+        assert_eq!(
+            error(read_pfm(b"PF\n4294967296 4294967296 -1.0\n")),
+            "image size 4294967296x4294967296 is too large"
+        );
+
+        // Fits in the value count, but not once multiplied by the sample size.
+        let wide = format!("P6\n{} 1 65535\n", usize::MAX / 3);
+        assert!(error(read_ppm(wide.as_bytes())).contains("is too large"));
+    }
+}
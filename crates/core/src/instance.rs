@@ -0,0 +1,207 @@
+use std::sync::Arc;
+
+use crate::{
+    aabb::Aabb,
+    hit::{HitRecord, Hittable},
+    interval::Interval,
+    ray::Ray,
+    transform::Transform,
+    vec3::{Point3, Vector3},
+};
+
+/// Places a shared object in the world through an affine transform.
+#[derive(Clone)]
+pub struct Instance {
+    object: Arc<dyn Hittable + Send + Sync + 'static>,
+    transform: Transform,
+    bbox: Aabb,
+}
+
+impl Instance {
+    pub fn new(object: Arc<dyn Hittable + Send + Sync + 'static>, transform: Transform) -> Self {
+        let bbox = transform.bbox(&object.bounding_box());
+        Self {
+            object,
+            transform,
+            bbox,
+        }
+    }
+
+    pub fn transform(&self) -> &Transform {
+        &self.transform
+    }
+}
+
+impl Hittable for Instance {
+    fn hit(&self, r: &Ray, interval: &Interval) -> Option<HitRecord> {
+        // The direction is not renormalized, so `t` means the same in both spaces.
+        let inv = self.transform.inverse();
+        let object_ray = Ray::with_time(inv.point(r.origin()), inv.vector(r.direction()), r.time());
+
+        let mut rec = self.object.hit(&object_ray, interval)?;
+
+        rec.p = self.transform.point(&rec.p);
+        rec.normal = self.transform.normal(&rec.normal).unit_vector();
+        rec.geometric_normal = self.transform.normal(&rec.geometric_normal).unit_vector();
+        rec.dpdu = self.transform.vector(&rec.dpdu);
+        rec.dpdv = self.transform.vector(&rec.dpdv);
+
+        Some(rec)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+
+    /// Evaluates the object's density in object space and converts it to
+    /// world-space solid angle. Mapping the unit direction `w` through the
+    /// inverse's linear part `A` scales solid angles by `|det A| / |A w|^3`,
+    /// which is 1 for rigid transforms.
+    fn pdf_value(&self, origin: &Point3, direction: &Vector3) -> f32 {
+        let inv = self.transform.inverse();
+        let object_direction = inv.vector(&direction.unit_vector());
+        let stretch = object_direction.length();
+        if stretch <= 0.0 {
+            return 0.0;
+        }
+
+        let pdf = self.object.pdf_value(&inv.point(origin), &object_direction);
+        pdf * inv.matrix().linear_determinant().abs() / (stretch * stretch * stretch)
+    }
+
+    fn random(&self, origin: &Point3) -> Vector3 {
+        let object_origin = self.transform.inverse().point(origin);
+        self.transform.vector(&self.object.random(&object_origin))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        material::{DiffuseLight, Lambertian},
+        quad::Quad,
+        sphere::Sphere,
+        vec3::Color,
+    };
+
+    #[test]
+    fn scaled_instance_hits_in_world_space() {
+        let mat = Arc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5)));
+        let sphere = Arc::new(Sphere::new(Point3::new(0.0, 0.0, 0.0), 1.0, mat));
+        // An ellipsoid with semi-axes 2, 1 and 1, centered at (0, 0, -5).
+        let transform = Transform::scale(2.0, 1.0, 1.0)
+            .then(Transform::translate(&Vector3::new(0.0, 0.0, -5.0)));
+        let instance = Instance::new(sphere, transform);
+        let any_t = Interval::new(0.001, f32::INFINITY);
+
+        // Along the stretched axis, from the side.
+        let r = Ray::new(Point3::new(5.0, 0.0, -5.0), Vector3::new(-2.0, 0.0, 0.0));
+        let rec = instance.hit(&r, &any_t).unwrap();
+        assert!((rec.t - 1.5).abs() < 1e-5);
+        assert!((rec.p - Point3::new(2.0, 0.0, -5.0)).length() < 1e-5);
+        assert!((rec.normal - Vector3::new(1.0, 0.0, 0.0)).length() < 1e-5);
+
+        // An oblique point on the ellipsoid, where the normal is not radial.
+        let p = Point3::new(2.0f32.sqrt(), 0.0, -5.0 + 0.5f32.sqrt());
+        let r = Ray::new(
+            p + Vector3::new(0.0, 0.0, 3.0),
+            Vector3::new(0.0, 0.0, -1.0),
+        );
+        let rec = instance.hit(&r, &any_t).unwrap();
+        assert!((rec.t - 3.0).abs() < 1e-4);
+        assert!((rec.p - p).length() < 1e-4);
+        // Gradient of x^2 / 4 + z^2 at the local point.
+        let expected = Vector3::new(2.0f32.sqrt() / 4.0, 0.0, 0.5f32.sqrt()).unit_vector();
+        assert!((rec.normal - expected).length() < 1e-4, "{:?}", rec.normal);
+        assert!((rec.normal.length() - 1.0).abs() < 1e-5);
+        assert!((rec.geometric_normal.length() - 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn rigid_instance_forwards_light_sampling() {
+        let light = Arc::new(DiffuseLight::new(Color::new(1.0, 1.0, 1.0)));
+        let quad: Arc<dyn Hittable + Send + Sync> = Arc::new(Quad::new(
+            Point3::new(-0.5, 0.0, -0.5),
+            Vector3::new(1.0, 0.0, 0.0),
+            Vector3::new(0.0, 0.0, 1.0),
+            light,
+        ));
+        let transform =
+            Transform::rotate_y(30.0).then(Transform::translate(&Vector3::new(3.0, 2.0, 1.0)));
+        let instance = Instance::new(quad.clone(), transform);
+
+        // Straight down onto the center: the same solid angle density as the
+        // untransformed quad seen from the matching point.
+        let world_origin = Point3::new(3.0, 0.0, 1.0);
+        let object_origin = Point3::new(0.0, -2.0, 0.0);
+        let down = Vector3::new(0.0, 1.0, 0.0);
+        let expected = quad.pdf_value(&object_origin, &down);
+        assert!(expected > 0.0);
+        assert!((instance.pdf_value(&world_origin, &down) - expected).abs() < 1e-4 * expected);
+
+        for _ in 0..100 {
+            let direction = instance.random(&world_origin);
+            let r = Ray::new(world_origin, direction);
+            assert!(
+                instance
+                    .hit(&r, &Interval::new(0.001, f32::INFINITY))
+                    .is_some()
+            );
+            assert!(instance.pdf_value(&world_origin, &direction) > 0.0);
+        }
+    }
+
+    #[test]
+    fn scaled_instance_pdf_is_in_world_space() {
+        let light = Arc::new(DiffuseLight::new(Color::new(1.0, 1.0, 1.0)));
+        let unit = Arc::new(Quad::new(
+            Point3::new(-0.5, 0.0, -0.5),
+            Vector3::new(1.0, 0.0, 0.0),
+            Vector3::new(0.0, 0.0, 1.0),
+            light.clone(),
+        ));
+        let transform = Transform::scale(4.0, 1.0, 1.0)
+            .then(Transform::rotate_x(20.0))
+            .then(Transform::translate(&Vector3::new(0.0, 2.0, 0.0)));
+        let instance = Instance::new(unit, transform);
+
+        // The same quad built directly in world space.
+        let world_quad = Quad::new(
+            transform.point(&Point3::new(-0.5, 0.0, -0.5)),
+            transform.vector(&Vector3::new(1.0, 0.0, 0.0)),
+            transform.vector(&Vector3::new(0.0, 0.0, 1.0)),
+            light,
+        );
+
+        let origin = Point3::new(0.3, 0.0, 0.2);
+        for direction in [
+            Vector3::new(0.0, 1.0, 0.0),
+            Vector3::new(1.2, 2.0, -0.1),
+            Vector3::new(-1.5, 2.0, -0.1),
+        ] {
+            let expected = world_quad.pdf_value(&origin, &direction);
+            assert!(expected > 0.0);
+            let pdf = instance.pdf_value(&origin, &direction);
+            assert!(
+                (pdf - expected).abs() < 1e-3 * expected,
+                "{} vs {}",
+                pdf,
+                expected
+            );
+        }
+
+        // Every sampled direction hits the instance with the density it reports.
+        for _ in 0..100 {
+            let direction = instance.random(&origin);
+            let expected = world_quad.pdf_value(&origin, &direction);
+            let pdf = instance.pdf_value(&origin, &direction);
+            assert!(
+                (pdf - expected).abs() < 1e-3 * expected,
+                "{} vs {}",
+                pdf,
+                expected
+            );
+        }
+    }
+}
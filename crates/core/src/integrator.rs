@@ -0,0 +1,291 @@
+use crate::{
+    camera::Background,
+    hit::{HitRecord, Hittable},
+    interval::Interval,
+    pdf::{CosinePdf, HittablePdf, Pdf, power_heuristic},
+    ray::Ray,
+    sampler::Sampler,
+    vec3::Color,
+};
+
+/// The scene and camera settings an integrator renders against.
+#[derive(Clone, Copy)]
+pub struct RenderContext<'a> {
+    pub world: &'a dyn Hittable,
+    pub background: &'a Background,
+    /// Emitters to sample directly; see `CameraBuilder::lights`.
+    pub lights: Option<&'a dyn Hittable>,
+    pub max_depth: usize,
+    /// Early termination of dim paths; `None` always traces to `max_depth`.
+    pub russian_roulette: Option<RussianRoulette>,
+}
+
+impl RenderContext<'_> {
+    fn hit(&self, r: &Ray) -> Option<HitRecord> {
+        self.world.hit(r, &Interval::new(0.001, f32::INFINITY))
+    }
+}
+
+/// Computes the color carried back to the camera along a ray.
+pub trait Integrator {
+    /// Return the color seen along `r`, drawing random decisions from `sampler`.
+    fn ray_color(&self, r: &Ray, ctx: &RenderContext, sampler: &mut dyn Sampler) -> Color;
+}
+
+/// Throughput-based path termination for `PathTracer`.
+///
+/// After `start_depth` bounces a path survives each further bounce with
+/// probability equal to its brightest throughput channel, but never below
+/// `min_survival`. Survivors are scaled up to keep the estimate unbiased.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RussianRoulette {
+    pub start_depth: usize,
+    pub min_survival: f32,
+}
+
+impl Default for RussianRoulette {
+    fn default() -> Self {
+        Self {
+            start_depth: 3,
+            min_survival: 0.05,
+        }
+    }
+}
+
+impl RussianRoulette {
+    /// Returns the factor to scale the throughput by, or `None` if the path
+    /// should stop here. `bounces` counts the scatter events so far.
+    fn survive(
+        &self,
+        bounces: usize,
+        throughput: &Color,
+        sampler: &mut dyn Sampler,
+    ) -> Option<f32> {
+        if bounces < self.start_depth {
+            return Some(1.0);
+        }
+
+        let brightest = throughput.x().max(*throughput.y()).max(*throughput.z());
+        let survival = brightest.clamp(self.min_survival, 1.0);
+        (sampler.get_1d() < survival).then(|| 1.0 / survival)
+    }
+}
+
+/// Unidirectional path tracing with next event estimation when lights are set.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct PathTracer;
+
+impl Integrator for PathTracer {
+    fn ray_color(&self, r: &Ray, ctx: &RenderContext, sampler: &mut dyn Sampler) -> Color {
+        let mut ray = *r;
+        let mut throughput = Color::new(1.0, 1.0, 1.0);
+        let mut color = Color::default();
+        // BSDF pdf of the bounce that produced `ray`, when light sampling also covered it.
+        let mut scattering_pdf = None;
+
+        for bounce in 0..ctx.max_depth {
+            let Some(rec) = ctx.hit(&ray) else {
+                color += throughput * ctx.background.color(&ray);
+                break;
+            };
+
+            let mut color_from_emission = rec.mat.emitted(&ray, &rec);
+            // The light sampled at the previous bounce may also have reached this emitter.
+            if let (Some(pdf), Some(lights)) = (scattering_pdf, ctx.lights) {
+                let light_pdf = lights.pdf_value(ray.origin(), ray.direction());
+                color_from_emission *= power_heuristic(pdf, light_pdf);
+            }
+            color += throughput * color_from_emission;
+
+            let Some((attenuation, scattered)) = rec.mat.scatter(&ray, &rec, sampler) else {
+                break;
+            };
+
+            let pdf = rec.mat.scattering_pdf(&ray, &rec, &scattered);
+            scattering_pdf = None;
+            if let Some(lights) = ctx.lights.filter(|_| pdf > 0.0) {
+                let color_from_lights = sample_lights(&ray, &rec, ctx.world, lights, true);
+                color += throughput * attenuation * color_from_lights;
+                scattering_pdf = Some(pdf);
+            }
+
+            throughput = throughput * attenuation;
+            if let Some(roulette) = &ctx.russian_roulette {
+                let Some(scale) = roulette.survive(bounce + 1, &throughput, sampler) else {
+                    break;
+                };
+                throughput *= scale;
+            }
+            ray = scattered;
+        }
+
+        color
+    }
+}
+
+/// Next event estimation: traces a shadow ray towards a point sampled on
+/// `lights` and returns the emitted light scaled by `scattering_pdf / pdf`.
+///
+/// With `mis` the result is also weighted against BSDF sampling with the
+/// power heuristic. The caller multiplies in the surface attenuation.
+fn sample_lights(
+    r: &Ray,
+    rec: &HitRecord,
+    world: &dyn Hittable,
+    lights: &dyn Hittable,
+    mis: bool,
+) -> Color {
+    let light_pdf = HittablePdf::new(lights, rec.p);
+    let direction = light_pdf.generate();
+    let pdf = light_pdf.value(&direction);
+    if pdf <= 0.0 {
+        return Color::default();
+    }
+
+    let shadow_ray = Ray::with_time(rec.p, direction, r.time());
+    let scattering_pdf = rec.mat.scattering_pdf(r, rec, &shadow_ray);
+    if scattering_pdf <= 0.0 {
+        return Color::default();
+    }
+
+    let Some(light_rec) = world.hit(&shadow_ray, &Interval::new(0.001, f32::INFINITY)) else {
+        return Color::default();
+    };
+    let emitted = light_rec.mat.emitted(&shadow_ray, &light_rec);
+
+    let weight = if mis {
+        power_heuristic(pdf, scattering_pdf)
+    } else {
+        1.0
+    };
+    weight * scattering_pdf / pdf * emitted
+}
+
+/// Ambient occlusion: the fraction of cosine-weighted directions above each
+/// hit that escape within `radius`, shown in grey.
+#[derive(Debug, Clone, Copy)]
+pub struct AmbientOcclusion {
+    pub samples: usize,
+    /// Occluders farther than this are ignored.
+    pub radius: f32,
+}
+
+impl AmbientOcclusion {
+    pub fn new(samples: usize, radius: f32) -> Self {
+        Self { samples, radius }
+    }
+}
+
+impl Default for AmbientOcclusion {
+    fn default() -> Self {
+        Self::new(16, f32::INFINITY)
+    }
+}
+
+impl Integrator for AmbientOcclusion {
+    fn ray_color(&self, r: &Ray, ctx: &RenderContext, sampler: &mut dyn Sampler) -> Color {
+        let Some(rec) = ctx.hit(r) else {
+            return ctx.background.color(r);
+        };
+        if self.samples == 0 {
+            return Color::default();
+        }
+
+        let hemisphere = CosinePdf::new(&rec.normal);
+        let range = Interval::new(0.001, self.radius);
+        let unoccluded = (0..self.samples)
+            .filter(|_| {
+                let probe = Ray::with_time(rec.p, hemisphere.sample(sampler.get_2d()), r.time());
+                ctx.world.hit(&probe, &range).is_none()
+            })
+            .count();
+
+        let visibility = unoccluded as f32 / self.samples as f32;
+        Color::new(visibility, visibility, visibility)
+    }
+}
+
+/// Whitted-style tracing: direct light at diffuse surfaces, perfect
+/// reflection and refraction through specular ones, and no indirect bounce.
+///
+/// Diffuse surfaces are lit only through `lights`; without them they render
+/// black apart from their own emission.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Whitted;
+
+impl Integrator for Whitted {
+    fn ray_color(&self, r: &Ray, ctx: &RenderContext, sampler: &mut dyn Sampler) -> Color {
+        Self::trace(r, ctx, ctx.max_depth, sampler)
+    }
+}
+
+impl Whitted {
+    fn trace(r: &Ray, ctx: &RenderContext, depth: usize, sampler: &mut dyn Sampler) -> Color {
+        if depth == 0 {
+            return Color::default();
+        }
+
+        let Some(rec) = ctx.hit(r) else {
+            return ctx.background.color(r);
+        };
+
+        let color_from_emission = rec.mat.emitted(r, &rec);
+        let Some((attenuation, scattered)) = rec.mat.scatter(r, &rec, sampler) else {
+            return color_from_emission;
+        };
+
+        if rec.mat.scattering_pdf(r, &rec, &scattered) <= 0.0 {
+            return color_from_emission
+                + attenuation * Self::trace(&scattered, ctx, depth - 1, sampler);
+        }
+
+        let color_from_lights = ctx.lights.map_or(Color::default(), |lights| {
+            sample_lights(r, &rec, ctx.world, lights, false) * attenuation
+        });
+        color_from_emission + color_from_lights
+    }
+}
+
+/// Which surface attribute `DebugView` displays.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DebugMode {
+    /// Shading normals mapped from `[-1, 1]` to `[0, 1]`.
+    Normals,
+    /// Distance along the ray, white at the camera fading to black at `max_distance`.
+    Depth { max_distance: f32 },
+    /// Surface attenuation, or emitted color for lights.
+    Albedo,
+}
+
+/// Shows a single surface attribute at the first hit; misses are black.
+#[derive(Debug, Clone, Copy)]
+pub struct DebugView {
+    pub mode: DebugMode,
+}
+
+impl DebugView {
+    pub fn new(mode: DebugMode) -> Self {
+        Self { mode }
+    }
+}
+
+impl Integrator for DebugView {
+    fn ray_color(&self, r: &Ray, ctx: &RenderContext, sampler: &mut dyn Sampler) -> Color {
+        let Some(rec) = ctx.hit(r) else {
+            return Color::default();
+        };
+
+        match self.mode {
+            DebugMode::Normals => 0.5 * (rec.normal + Color::new(1.0, 1.0, 1.0)),
+            DebugMode::Depth { max_distance } => {
+                let distance = rec.t * r.direction().length();
+                let shade = 1.0 - Interval::new(0.0, 1.0).clamp(distance / max_distance);
+                Color::new(shade, shade, shade)
+            }
+            DebugMode::Albedo => match rec.mat.scatter(r, &rec, sampler) {
+                Some((attenuation, _)) => attenuation,
+                None => rec.mat.emitted(r, &rec),
+            },
+        }
+    }
+}
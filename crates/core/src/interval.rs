@@ -1,5 +1,6 @@
 use core::f32;
 
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Interval {
     pub min: f32,
     pub max: f32,
@@ -20,6 +21,11 @@ impl Interval {
         Self { min, max }
     }
 
+    /// Creates the tightest interval enclosing both `a` and `b`.
+    pub const fn enclosing(a: &Interval, b: &Interval) -> Self {
+        Self::new(a.min.min(b.min), a.max.max(b.max))
+    }
+
     pub const fn size(&self) -> f32 {
         self.max - self.min
     }
@@ -32,6 +38,11 @@ impl Interval {
         self.min < x && x < self.max
     }
 
+    pub const fn expand(&self, delta: f32) -> Self {
+        let padding = delta / 2.0;
+        Self::new(self.min - padding, self.max + padding)
+    }
+
     pub const fn clamp(&self, x: f32) -> f32 {
         if x < self.min {
             self.min
@@ -1,8 +1,25 @@
+pub mod aabb;
+pub mod bvh;
 pub mod camera;
+pub mod film;
 pub mod hit;
+pub mod input;
+pub mod instance;
+pub mod integrator;
 pub mod interval;
 pub mod material;
+pub mod medium;
+pub mod noise;
+pub mod obj;
+pub mod output;
+pub mod pdf;
+pub mod quad;
 pub mod ray;
+pub mod rng;
+pub mod sampler;
 pub mod sphere;
+pub mod texture;
+pub mod transform;
+pub mod triangle;
 pub mod utils;
 pub mod vec3;
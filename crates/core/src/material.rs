@@ -1,65 +1,111 @@
-use std::ops::Neg;
+use std::{f32::consts::PI, ops::Neg, sync::Arc};
 
 use crate::{
     hit::HitRecord,
     ray::Ray,
+    sampler::Sampler,
+    texture::{SolidColor, Texture},
     vec3::{Color, Vector3},
 };
 
 pub trait Material {
-    /// Return attenuation and scattered ray if the ray is scattered.
-    fn scatter(&self, _ray_in: &Ray, _rec: &HitRecord) -> Option<(Color, Ray)> {
+    /// Return attenuation and scattered ray if the ray is scattered, drawing
+    /// any random choices from `sampler`.
+    fn scatter(
+        &self,
+        _ray_in: &Ray,
+        _rec: &HitRecord,
+        _sampler: &mut dyn Sampler,
+    ) -> Option<(Color, Ray)> {
         None
     }
+
+    /// Return the density, with respect to solid angle, with which `scatter`
+    /// picks the direction of `scattered`.
+    ///
+    /// Zero means the material scatters into a single (specular) direction and
+    /// cannot be light sampled. Otherwise the scatter attenuation times this
+    /// value must be the BSDF times the cosine term for that direction.
+    fn scattering_pdf(&self, _ray_in: &Ray, _rec: &HitRecord, _scattered: &Ray) -> f32 {
+        0.0
+    }
+
+    /// Return the light emitted from the hit point.
+    fn emitted(&self, _ray_in: &Ray, _rec: &HitRecord) -> Color {
+        Color::default()
+    }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct Lambertian {
-    albedo: Color,
+    tex: Arc<dyn Texture + Send + Sync + 'static>,
 }
 
 impl Material for Lambertian {
-    fn scatter(&self, _ray_in: &Ray, rec: &HitRecord) -> Option<(Color, Ray)> {
-        let mut scatter_direction = rec.normal + Vector3::random_unit_vector();
+    fn scatter(
+        &self,
+        ray_in: &Ray,
+        rec: &HitRecord,
+        sampler: &mut dyn Sampler,
+    ) -> Option<(Color, Ray)> {
+        let mut scatter_direction = rec.normal + Vector3::sample_unit_vector(sampler.get_2d());
         if scatter_direction.near_zero() {
             scatter_direction = rec.normal;
         }
 
-        let scattered = Ray::new(rec.p, scatter_direction);
-        let attenuation = self.albedo;
+        let scattered = Ray::with_time(rec.p, scatter_direction, ray_in.time());
+        let attenuation = self.tex.value(rec.u, rec.v, &rec.p);
         Some((attenuation, scattered))
     }
+
+    fn scattering_pdf(&self, _ray_in: &Ray, rec: &HitRecord, scattered: &Ray) -> f32 {
+        let cos_theta = rec.normal.dot(&scattered.direction().unit_vector());
+        (cos_theta / PI).max(0.0)
+    }
 }
 
 impl Lambertian {
     pub fn new(albedo: Color) -> Self {
-        Self { albedo }
+        Self::from_texture(Arc::new(SolidColor::new(albedo)))
+    }
+
+    pub fn from_texture(tex: Arc<dyn Texture + Send + Sync + 'static>) -> Self {
+        Self { tex }
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct Metal {
-    albedo: Color,
+    tex: Arc<dyn Texture + Send + Sync + 'static>,
     fuzz: f32,
 }
 
 impl Metal {
     pub fn new(albedo: Color, fuzz: f32) -> Self {
+        Self::from_texture(Arc::new(SolidColor::new(albedo)), fuzz)
+    }
+
+    pub fn from_texture(tex: Arc<dyn Texture + Send + Sync + 'static>, fuzz: f32) -> Self {
         Self {
-            albedo,
+            tex,
             fuzz: fuzz.clamp(0.0, 1.0),
         }
     }
 }
 
 impl Material for Metal {
-    fn scatter(&self, ray_in: &Ray, rec: &HitRecord) -> Option<(Color, Ray)> {
+    fn scatter(
+        &self,
+        ray_in: &Ray,
+        rec: &HitRecord,
+        sampler: &mut dyn Sampler,
+    ) -> Option<(Color, Ray)> {
         let reflected = ray_in.direction().reflect(&rec.normal).unit_vector()
-            + self.fuzz * Vector3::random_unit_vector();
-        let scattered = Ray::new(rec.p, reflected);
+            + self.fuzz * Vector3::sample_unit_vector(sampler.get_2d());
+        let scattered = Ray::with_time(rec.p, reflected, ray_in.time());
 
         if scattered.direction().dot(&rec.normal) > 0.0 {
-            Some((self.albedo, scattered))
+            Some((self.tex.value(rec.u, rec.v, &rec.p), scattered))
         } else {
             None
         }
@@ -78,7 +124,12 @@ impl Dielectric {
 }
 
 impl Material for Dielectric {
-    fn scatter(&self, ray_in: &Ray, rec: &HitRecord) -> Option<(Color, Ray)> {
+    fn scatter(
+        &self,
+        ray_in: &Ray,
+        rec: &HitRecord,
+        sampler: &mut dyn Sampler,
+    ) -> Option<(Color, Ray)> {
         let attenuation = Color::new(1.0, 1.0, 1.0);
         let ri = if rec.front_face {
             1.0 / self.refraction_index
@@ -91,14 +142,13 @@ impl Material for Dielectric {
         let sin_theta = (1.0 - cos_theta * cos_theta).sqrt();
 
         let cannot_refract = ri * sin_theta > 1.0;
-        let direction =
-            if cannot_refract || reflectance(cos_theta, ri) > rand::random_range(0.0..=1.0) {
-                unit_direction.reflect(&rec.normal)
-            } else {
-                unit_direction.refract(&rec.normal, ri)
-            };
-
-        let scattered = Ray::new(rec.p, direction);
+        let direction = if cannot_refract || reflectance(cos_theta, ri) > sampler.get_1d() {
+            unit_direction.reflect(&rec.normal)
+        } else {
+            unit_direction.refract(&rec.normal, ri)
+        };
+
+        let scattered = Ray::with_time(rec.p, direction, ray_in.time());
         Some((attenuation, scattered))
     }
 }
@@ -109,3 +159,61 @@ fn reflectance(cosine: f32, refraction_index: f32) -> f32 {
     let r0 = r0 * r0;
     r0 + (1.0 - r0) * (1.0 - cosine).powf(5.0)
 }
+
+#[derive(Clone)]
+pub struct DiffuseLight {
+    tex: Arc<dyn Texture + Send + Sync + 'static>,
+}
+
+impl DiffuseLight {
+    pub fn new(emit: Color) -> Self {
+        Self::from_texture(Arc::new(SolidColor::new(emit)))
+    }
+
+    pub fn from_texture(tex: Arc<dyn Texture + Send + Sync + 'static>) -> Self {
+        Self { tex }
+    }
+}
+
+impl Material for DiffuseLight {
+    fn emitted(&self, _ray_in: &Ray, rec: &HitRecord) -> Color {
+        self.tex.value(rec.u, rec.v, &rec.p)
+    }
+}
+
+/// Scatters uniformly in all directions, the phase function of an isotropic medium.
+#[derive(Clone)]
+pub struct Isotropic {
+    tex: Arc<dyn Texture + Send + Sync + 'static>,
+}
+
+impl Isotropic {
+    pub fn new(albedo: Color) -> Self {
+        Self::from_texture(Arc::new(SolidColor::new(albedo)))
+    }
+
+    pub fn from_texture(tex: Arc<dyn Texture + Send + Sync + 'static>) -> Self {
+        Self { tex }
+    }
+}
+
+impl Material for Isotropic {
+    fn scatter(
+        &self,
+        ray_in: &Ray,
+        rec: &HitRecord,
+        sampler: &mut dyn Sampler,
+    ) -> Option<(Color, Ray)> {
+        let scattered = Ray::with_time(
+            rec.p,
+            Vector3::sample_unit_vector(sampler.get_2d()),
+            ray_in.time(),
+        );
+        let attenuation = self.tex.value(rec.u, rec.v, &rec.p);
+        Some((attenuation, scattered))
+    }
+
+    fn scattering_pdf(&self, _ray_in: &Ray, _rec: &HitRecord, _scattered: &Ray) -> f32 {
+        1.0 / (4.0 * PI)
+    }
+}
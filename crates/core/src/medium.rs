@@ -0,0 +1,92 @@
+use std::sync::Arc;
+
+use crate::{
+    aabb::Aabb,
+    hit::{HitRecord, Hittable},
+    interval::{self, Interval},
+    material::{Isotropic, Material},
+    ray::Ray,
+    rng,
+    texture::Texture,
+    vec3::{Color, Vector3},
+};
+
+/// A homogeneous participating medium (smoke, fog) filling a boundary shape.
+///
+/// The boundary must be closed and convex: a ray is assumed to enter and leave it once.
+#[derive(Clone)]
+pub struct ConstantMedium {
+    boundary: Arc<dyn Hittable + Send + Sync + 'static>,
+    neg_inv_density: f32,
+    phase_function: Arc<dyn Material + Send + Sync + 'static>,
+}
+
+impl ConstantMedium {
+    pub fn new(
+        boundary: Arc<dyn Hittable + Send + Sync + 'static>,
+        density: f32,
+        tex: Arc<dyn Texture + Send + Sync + 'static>,
+    ) -> Self {
+        Self {
+            boundary,
+            neg_inv_density: -1.0 / density,
+            phase_function: Arc::new(Isotropic::from_texture(tex)),
+        }
+    }
+
+    pub fn from_color(
+        boundary: Arc<dyn Hittable + Send + Sync + 'static>,
+        density: f32,
+        albedo: Color,
+    ) -> Self {
+        Self {
+            boundary,
+            neg_inv_density: -1.0 / density,
+            phase_function: Arc::new(Isotropic::new(albedo)),
+        }
+    }
+}
+
+impl Hittable for ConstantMedium {
+    fn hit(&self, r: &Ray, interval: &Interval) -> Option<HitRecord> {
+        let rec1 = self.boundary.hit(r, &interval::UNIVERSE)?;
+        let rec2 = self
+            .boundary
+            .hit(r, &Interval::new(rec1.t + 0.0001, f32::INFINITY))?;
+
+        let t_enter = rec1.t.max(interval.min).max(0.0);
+        let t_exit = rec2.t.min(interval.max);
+        if t_enter >= t_exit {
+            return None;
+        }
+
+        // Sample an exponentially distributed distance to the next scattering event.
+        let ray_length = r.direction().length();
+        let distance_inside_boundary = (t_exit - t_enter) * ray_length;
+        let hit_distance = self.neg_inv_density * rng::random_range(f32::EPSILON..1.0).ln();
+        if hit_distance > distance_inside_boundary {
+            return None;
+        }
+
+        let t = t_enter + hit_distance / ray_length;
+
+        // Normal, facing and surface coordinates are meaningless inside a volume.
+        let normal = Vector3::new(1.0, 0.0, 0.0);
+        Some(HitRecord {
+            p: r.at(t),
+            normal,
+            geometric_normal: normal,
+            t,
+            u: 0.0,
+            v: 0.0,
+            dpdu: Vector3::new(0.0, 1.0, 0.0),
+            dpdv: Vector3::new(0.0, 0.0, 1.0),
+            front_face: true,
+            mat: self.phase_function.clone(),
+        })
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.boundary.bounding_box()
+    }
+}
@@ -0,0 +1,485 @@
+use std::{collections::HashMap, fs, path::Path, sync::Arc};
+
+use anyhow::{Context, anyhow};
+
+use crate::{
+    hit::HittableList,
+    material::{Dielectric, Lambertian, Material, Metal},
+    triangle::TriangleMesh,
+    vec3::{Color, Point3, Vector3},
+};
+
+type MaterialRef = Arc<dyn Material + Send + Sync + 'static>;
+
+/// Loads a Wavefront OBJ file, resolving `mtllib` statements relative to it.
+///
+/// Faces are grouped by material, and every group becomes one [`TriangleMesh`].
+pub fn load_obj<P: AsRef<Path>>(path: P) -> anyhow::Result<HittableList> {
+    let path = path.as_ref();
+    let source =
+        fs::read_to_string(path).with_context(|| format!("failed to read '{}'", path.display()))?;
+    let base_dir = path.parent().unwrap_or(Path::new(""));
+
+    parse_obj(&source, &path.display().to_string(), |lib| {
+        let lib_path = base_dir.join(lib);
+        let source = fs::read_to_string(&lib_path)
+            .with_context(|| format!("failed to read '{}'", lib_path.display()))?;
+        parse_mtl(&source, &lib_path.display().to_string())
+    })
+}
+
+/// Parses OBJ `source`; `name` is only used in error messages.
+///
+/// `load_mtl` is called once for every file named by a `mtllib` statement.
+/// Statements the renderer has no use for, such as free-form geometry or
+/// texture maps, are skipped.
+pub fn parse_obj<F>(source: &str, name: &str, mut load_mtl: F) -> anyhow::Result<HittableList>
+where
+    F: FnMut(&str) -> anyhow::Result<HashMap<String, MaterialRef>>,
+{
+    let default_mat: MaterialRef = Arc::new(Lambertian::new(Color::new(0.8, 0.8, 0.8)));
+
+    let mut positions = Vec::new();
+    let mut normals = Vec::new();
+    let mut uvs = Vec::new();
+
+    let mut materials = HashMap::new();
+    let mut groups: Vec<FaceGroup> = Vec::new();
+    let mut current = None::<usize>;
+
+    for (lineno, line) in source.lines().enumerate() {
+        let lineno = lineno + 1;
+        let err = |msg: String| anyhow!("{}:{}: {}", name, lineno, msg);
+
+        let mut parts = strip_comment(line).split_whitespace();
+        let Some(keyword) = parts.next() else {
+            continue;
+        };
+        let args = parts.collect::<Vec<_>>();
+
+        match keyword {
+            "v" => positions.push(parse_vec3(&args).map_err(err)?),
+            "vn" => normals.push(parse_vec3(&args).map_err(err)?),
+            "vt" => {
+                let uv = parse_floats(&args, 1).map_err(err)?;
+                uvs.push((uv[0], uv.get(1).copied().unwrap_or(0.0)));
+            }
+            "f" => {
+                if args.len() < 3 {
+                    return Err(err(format!(
+                        "face needs at least 3 vertices, got {}",
+                        args.len()
+                    )));
+                }
+                let corners = args
+                    .iter()
+                    .map(|arg| parse_corner(arg, positions.len(), uvs.len(), normals.len()))
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(err)?;
+
+                let group = match current {
+                    Some(group) => group,
+                    None => {
+                        groups.push(FaceGroup::new(default_mat.clone()));
+                        current = Some(groups.len() - 1);
+                        groups.len() - 1
+                    }
+                };
+                // Triangulate polygons as a fan around the first corner.
+                for i in 1..corners.len() - 1 {
+                    groups[group].add_face([corners[0], corners[i], corners[i + 1]]);
+                }
+            }
+            "mtllib" => {
+                if args.is_empty() {
+                    return Err(err("mtllib needs a file name".to_string()));
+                }
+                for lib in &args {
+                    let loaded = load_mtl(lib).with_context(|| {
+                        format!(
+                            "{}:{}: failed to load material library '{}'",
+                            name, lineno, lib
+                        )
+                    })?;
+                    materials.extend(loaded);
+                }
+            }
+            "usemtl" => {
+                let mat_name = args.join(" ");
+                let mat = materials
+                    .get(&mat_name)
+                    .cloned()
+                    .ok_or_else(|| err(format!("unknown material '{}'", mat_name)))?;
+                groups.push(FaceGroup::new(mat));
+                current = Some(groups.len() - 1);
+            }
+            // Grouping, smoothing, lines, points, free-form geometry and texture
+            // map statements are not supported and skipped.
+            _ => {}
+        }
+    }
+
+    let mut list = HittableList::default();
+    for group in groups.into_iter().filter(|g| !g.indices.is_empty()) {
+        list.add(Arc::new(group.into_mesh(&positions, &uvs, &normals)?));
+    }
+    Ok(list)
+}
+
+/// Parses a Wavefront MTL library into the renderer's materials.
+///
+/// Materials with `d < 1` (or `Tr > 0`) become [`Dielectric`] with index `Ni`,
+/// those with `illum 3` or a specular color brighter than the diffuse one become
+/// [`Metal`] tinted by `Ks` with fuzz derived from `Ns`, and the rest become
+/// [`Lambertian`] with albedo `Kd`.
+pub fn parse_mtl(source: &str, name: &str) -> anyhow::Result<HashMap<String, MaterialRef>> {
+    let mut materials = HashMap::new();
+    let mut current: Option<(String, MtlParams)> = None;
+
+    for (lineno, line) in source.lines().enumerate() {
+        let lineno = lineno + 1;
+        let err = |msg: String| anyhow!("{}:{}: {}", name, lineno, msg);
+
+        let mut parts = strip_comment(line).split_whitespace();
+        let Some(keyword) = parts.next() else {
+            continue;
+        };
+        let args = parts.collect::<Vec<_>>();
+
+        if keyword == "newmtl" {
+            if args.is_empty() {
+                return Err(err("newmtl needs a material name".to_string()));
+            }
+            if let Some((mat_name, params)) = current.take() {
+                materials.insert(mat_name, params.to_material());
+            }
+            current = Some((args.join(" "), MtlParams::default()));
+            continue;
+        }
+
+        let Some((_, params)) = current.as_mut() else {
+            return Err(err(format!("'{}' before any newmtl", keyword)));
+        };
+        let color = |args: &[&str]| -> anyhow::Result<Color> {
+            let c = parse_floats(args, 1).map_err(err)?;
+            // A single value means a grey color.
+            Ok(match c[..] {
+                [v] => Color::new(v, v, v),
+                [r, g, b, ..] => Color::new(r, g, b),
+                _ => return Err(err(format!("expected 1 or 3 values, got {}", c.len()))),
+            })
+        };
+        let scalar = |args: &[&str]| parse_floats(args, 1).map(|v| v[0]).map_err(err);
+
+        match keyword {
+            "Kd" => params.kd = color(&args)?,
+            "Ks" => params.ks = color(&args)?,
+            "Ns" => params.ns = scalar(&args)?,
+            "Ni" => params.ni = scalar(&args)?,
+            "d" => params.d = scalar(&args)?,
+            "Tr" => params.d = 1.0 - scalar(&args)?,
+            "illum" => params.illum = scalar(&args)? as i32,
+            // Other statements (ambient, emission, texture maps) are not supported and skipped.
+            _ => {}
+        }
+    }
+
+    if let Some((mat_name, params)) = current.take() {
+        materials.insert(mat_name, params.to_material());
+    }
+    Ok(materials)
+}
+
+#[derive(Debug, Clone)]
+struct MtlParams {
+    kd: Color,
+    ks: Color,
+    ns: f32,
+    ni: f32,
+    d: f32,
+    illum: i32,
+}
+
+impl Default for MtlParams {
+    fn default() -> Self {
+        Self {
+            kd: Color::new(0.8, 0.8, 0.8),
+            ks: Color::default(),
+            ns: 0.0,
+            ni: 1.5,
+            d: 1.0,
+            illum: 2,
+        }
+    }
+}
+
+impl MtlParams {
+    fn to_material(&self) -> MaterialRef {
+        let max = |c: &Color| c.x().max(*c.y()).max(*c.z());
+
+        if self.d < 1.0 {
+            Arc::new(Dielectric::new(self.ni))
+        } else if self.illum == 3 || max(&self.ks) > max(&self.kd) {
+            // Map the Phong exponent to a roughness-like fuzz factor.
+            let fuzz = (2.0 / (self.ns.max(0.0) + 2.0)).sqrt();
+            Arc::new(Metal::new(self.ks, fuzz))
+        } else {
+            Arc::new(Lambertian::new(self.kd))
+        }
+    }
+}
+
+/// One face corner: 0-based indices into the position, uv and normal arrays.
+type Corner = (usize, Option<usize>, Option<usize>);
+
+/// Faces sharing one material, with vertices deduplicated per corner triple.
+struct FaceGroup {
+    mat: MaterialRef,
+    corners: Vec<Corner>,
+    lookup: HashMap<Corner, usize>,
+    indices: Vec<[usize; 3]>,
+}
+
+impl FaceGroup {
+    fn new(mat: MaterialRef) -> Self {
+        Self {
+            mat,
+            corners: Vec::new(),
+            lookup: HashMap::new(),
+            indices: Vec::new(),
+        }
+    }
+
+    fn add_face(&mut self, face: [Corner; 3]) {
+        let face = face.map(|corner| {
+            *self.lookup.entry(corner).or_insert_with(|| {
+                self.corners.push(corner);
+                self.corners.len() - 1
+            })
+        });
+        self.indices.push(face);
+    }
+
+    fn into_mesh(
+        self,
+        positions: &[Point3],
+        uvs: &[(f32, f32)],
+        normals: &[Vector3],
+    ) -> anyhow::Result<TriangleMesh> {
+        let mesh_positions = self.corners.iter().map(|c| positions[c.0]).collect();
+        // Attributes are only kept if every corner in the group provides them.
+        let mesh_uvs = self
+            .corners
+            .iter()
+            .map(|c| c.1.map(|i| uvs[i]))
+            .collect::<Option<Vec<_>>>();
+        let mesh_normals = self
+            .corners
+            .iter()
+            .map(|c| c.2.map(|i| normals[i]))
+            .collect::<Option<Vec<_>>>();
+
+        TriangleMesh::new(
+            mesh_positions,
+            mesh_normals,
+            mesh_uvs,
+            self.indices,
+            self.mat,
+        )
+    }
+}
+
+fn strip_comment(line: &str) -> &str {
+    line.split_once('#').map_or(line, |(content, _)| content)
+}
+
+fn parse_floats(args: &[&str], min: usize) -> Result<Vec<f32>, String> {
+    if args.len() < min {
+        return Err(format!(
+            "expected at least {} values, got {}",
+            min,
+            args.len()
+        ));
+    }
+    args.iter()
+        .map(|arg| {
+            arg.parse::<f32>()
+                .map_err(|_| format!("invalid number '{}'", arg))
+        })
+        .collect()
+}
+
+/// Parses the first three values; an optional fourth (`w`) is ignored.
+fn parse_vec3(args: &[&str]) -> Result<Vector3, String> {
+    let v = parse_floats(args, 3)?;
+    Ok(Vector3::new(v[0], v[1], v[2]))
+}
+
+/// Parses a `v`, `v/vt`, `v//vn` or `v/vt/vn` face corner.
+fn parse_corner(
+    arg: &str,
+    num_positions: usize,
+    num_uvs: usize,
+    num_normals: usize,
+) -> Result<Corner, String> {
+    let mut fields = arg.split('/');
+    let v = fields.next().unwrap_or_default();
+    let vt = fields.next().filter(|s| !s.is_empty());
+    let vn = fields.next().filter(|s| !s.is_empty());
+    if fields.next().is_some() {
+        return Err(format!("malformed face corner '{}'", arg));
+    }
+
+    let position = resolve_index(v, num_positions, "vertex")?;
+    let uv = vt
+        .map(|vt| resolve_index(vt, num_uvs, "texture coordinate"))
+        .transpose()?;
+    let normal = vn
+        .map(|vn| resolve_index(vn, num_normals, "normal"))
+        .transpose()?;
+    Ok((position, uv, normal))
+}
+
+/// Resolves a 1-based (or negative, relative) OBJ index into a 0-based one.
+fn resolve_index(s: &str, len: usize, what: &str) -> Result<usize, String> {
+    let idx = s
+        .parse::<i64>()
+        .map_err(|_| format!("invalid {} index '{}'", what, s))?;
+    let resolved = match idx {
+        0 => None,
+        i if i > 0 => Some(i as usize - 1),
+        i => (len as i64)
+            .checked_add(i)
+            .and_then(|i| usize::try_from(i).ok()),
+    };
+
+    resolved
+        .filter(|&i| i < len)
+        .ok_or_else(|| format!("{} index {} is out of range ({} defined)", what, idx, len))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        hit::Hittable, interval::Interval, ray::Ray, sampler::IndependentSampler, sphere::Sphere,
+    };
+
+    fn no_mtl(lib: &str) -> anyhow::Result<HashMap<String, MaterialRef>> {
+        Err(anyhow!("unexpected mtllib '{}'", lib))
+    }
+
+    fn parse_err(source: &str) -> String {
+        match parse_obj(source, "t.obj", no_mtl) {
+            Ok(_) => panic!("expected a parse error"),
+            Err(e) => e.to_string(),
+        }
+    }
+
+    fn hits(list: &HittableList, x: f32, y: f32) -> bool {
+        let r = Ray::new(Point3::new(x, y, 1.0), Vector3::new(0.0, 0.0, -1.0));
+        list.hit(&r, &Interval::new(0.001, f32::INFINITY)).is_some()
+    }
+
+    #[test]
+    fn face_index_errors_report_the_line() {
+        let out_of_range = "v 0 0 0\nv 1 0 0\nv 0 1 0\n\nf 1 2 4\n";
+        assert_eq!(
+            parse_err(out_of_range),
+            "t.obj:5: vertex index 4 is out of range (3 defined)"
+        );
+
+        let negative = "v 0 0 0\nv 1 0 0\nf -1 -2 -3\n";
+        assert_eq!(
+            parse_err(negative),
+            "t.obj:3: vertex index -3 is out of range (2 defined)"
+        );
+
+        let zero = "v 0 0 0\nv 1 0 0\nv 0 1 0\nf 0 1 2\n";
+        assert!(parse_err(zero).starts_with("t.obj:4: "));
+    }
+
+    #[test]
+    fn unknown_material_is_an_error() {
+        let source = "v 0 0 0\nusemtl missing\n";
+        assert_eq!(parse_err(source), "t.obj:2: unknown material 'missing'");
+    }
+
+    #[test]
+    fn polygons_are_fan_triangulated() {
+        let source = "v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nf 1 2 3 4\n";
+        let list = parse_obj(source, "t.obj", no_mtl).unwrap();
+
+        assert_eq!(list.objects.len(), 1);
+        // One point inside each of the fan triangles (1, 2, 3) and (1, 3, 4).
+        assert!(hits(&list, 0.8, 0.2));
+        assert!(hits(&list, 0.2, 0.8));
+        assert!(!hits(&list, 1.5, 0.5));
+    }
+
+    #[test]
+    fn unsupported_statements_are_skipped() {
+        let source = "vp 0.5 0.5\ncstype bspline\nusemap wood\nmg 1 0.5\n\
+                      v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n";
+        let list = parse_obj(source, "t.obj", no_mtl).unwrap();
+        assert!(hits(&list, 0.2, 0.2));
+    }
+
+    #[test]
+    fn mtllib_loads_every_library() {
+        let mut requested = Vec::new();
+        let source = "mtllib a.mtl b.mtl\nusemtl from_b\n";
+        parse_obj(source, "t.obj", |lib| {
+            requested.push(lib.to_string());
+            parse_mtl(&format!("newmtl from_{}\nKd 1 0 0\n", &lib[..1]), lib)
+        })
+        .unwrap();
+        assert_eq!(requested, ["a.mtl", "b.mtl"]);
+    }
+
+    /// Scatters a ray hitting the front of a unit sphere made of `mat`.
+    fn scatter(mat: &MaterialRef) -> (Color, Ray, f32) {
+        let sphere = Sphere::new(Point3::new(0.0, 0.0, 0.0), 1.0, mat.clone());
+        let r = Ray::new(Point3::new(0.0, 0.0, 5.0), Vector3::new(0.0, 0.0, -1.0));
+        let rec = sphere
+            .hit(&r, &Interval::new(0.001, f32::INFINITY))
+            .unwrap();
+        let (attenuation, scattered) = rec.mat.scatter(&r, &rec, &mut IndependentSampler).unwrap();
+        let pdf = rec.mat.scattering_pdf(&r, &rec, &scattered);
+        (attenuation, scattered, pdf)
+    }
+
+    #[test]
+    fn mtl_parameters_pick_the_material() {
+        let source = "\
+            newmtl matte\n Kd 0.1 0.2 0.3\n\
+            newmtl mirror\n Kd 0.1 0.1 0.1\n Ks 0.9 0.8 0.7\n Ns 1000\n\
+            newmtl glass\n Kd 0.5 0.5 0.5\n d 0.5\n Ni 1.5\n";
+        let materials = parse_mtl(source, "t.mtl").unwrap();
+        assert_eq!(materials.len(), 3);
+
+        // Lambertian: diffuse albedo and a cosine density.
+        let (attenuation, _, pdf) = scatter(&materials["matte"]);
+        assert_eq!(attenuation, Color::new(0.1, 0.2, 0.3));
+        assert!(pdf > 0.0);
+
+        // Metal: specular tint, and a high Ns means a nearly perfect reflection.
+        let (attenuation, scattered, pdf) = scatter(&materials["mirror"]);
+        assert_eq!(attenuation, Color::new(0.9, 0.8, 0.7));
+        assert_eq!(pdf, 0.0);
+        assert!(scattered.direction().unit_vector().z() > &0.99);
+
+        // Dielectric: clear and specular.
+        let (attenuation, _, pdf) = scatter(&materials["glass"]);
+        assert_eq!(attenuation, Color::new(1.0, 1.0, 1.0));
+        assert_eq!(pdf, 0.0);
+    }
+
+    #[test]
+    fn mtl_errors_report_the_line() {
+        let Err(err) = parse_mtl("newmtl a\nKd 0.1 x 0.3\n", "t.mtl") else {
+            panic!("expected a parse error");
+        };
+        assert_eq!(err.to_string(), "t.mtl:2: invalid number 'x'");
+    }
+}
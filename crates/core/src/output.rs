@@ -0,0 +1,64 @@
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+use anyhow::bail;
+
+use crate::film::Film;
+
+mod deflate;
+mod exr;
+mod hdr;
+mod pfm;
+mod png;
+mod ppm;
+
+pub use exr::{ExrCompression, ExrPixelType, ExrWriter};
+pub use hdr::HdrWriter;
+pub use pfm::PfmWriter;
+pub use png::{PngBitDepth, PngWriter};
+pub use ppm::{PpmBinaryWriter, PpmWriter};
+
+/// Encodes a [`Film`] into some image format.
+pub trait ImageWriter {
+    fn write(&self, film: &Film, out: &mut dyn Write) -> anyhow::Result<()>;
+}
+
+/// Picks a writer based on the extension of `path`.
+pub fn writer_for_path(path: &Path) -> anyhow::Result<Box<dyn ImageWriter>> {
+    let ext = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_ascii_lowercase());
+
+    match ext.as_deref() {
+        Some("ppm") => Ok(Box::new(PpmBinaryWriter)),
+        Some("png") => Ok(Box::new(PngWriter::default())),
+        Some("pfm") => Ok(Box::new(PfmWriter)),
+        Some("hdr") => Ok(Box::new(HdrWriter)),
+        Some("exr") => Ok(Box::new(ExrWriter::default())),
+        Some(ext) => bail!("unsupported image format '.{}'", ext),
+        None => bail!("cannot infer image format of '{}'", path.display()),
+    }
+}
+
+/// Writes `film` to `path`, choosing the format from the file extension.
+pub fn save<P: AsRef<Path>>(film: &Film, path: P) -> anyhow::Result<()> {
+    let path = path.as_ref();
+    save_with(film, path, writer_for_path(path)?.as_ref())
+}
+
+/// Writes `film` to `path` using an explicitly chosen writer.
+pub fn save_with<P: AsRef<Path>>(
+    film: &Film,
+    path: P,
+    writer: &dyn ImageWriter,
+) -> anyhow::Result<()> {
+    let mut out = BufWriter::new(File::create(path)?);
+    writer.write(film, &mut out)?;
+    out.flush()?;
+
+    Ok(())
+}
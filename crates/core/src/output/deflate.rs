@@ -0,0 +1,320 @@
+//! A small, self-contained zlib (RFC 1950) / DEFLATE (RFC 1951) encoder.
+//!
+//! Uses LZ77 with hash chains and the fixed Huffman code tables, which gives a
+//! reasonable ratio on rendered images without any external dependency.
+
+const WINDOW_SIZE: usize = 1 << 15;
+const MIN_MATCH: usize = 3;
+const MAX_MATCH: usize = 258;
+const MAX_CHAIN: usize = 64;
+
+const HASH_BITS: u32 = 15;
+const HASH_SIZE: usize = 1 << HASH_BITS;
+
+const NIL: usize = usize::MAX;
+
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+const LENGTH_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+const DIST_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DIST_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+
+/// Compresses `data` into a complete zlib stream.
+pub fn zlib_compress(data: &[u8]) -> Vec<u8> {
+    // CMF: deflate with a 32K window; FLG: no dictionary, check bits only.
+    let mut out = vec![0x78, 0x01];
+    out.extend(deflate(data));
+    out.extend(adler32(data).to_be_bytes());
+    out
+}
+
+/// Compresses `data` into a raw DEFLATE stream made of a single fixed-Huffman block.
+pub fn deflate(data: &[u8]) -> Vec<u8> {
+    let mut w = BitWriter::default();
+    // BFINAL = 1, BTYPE = 01 (fixed Huffman codes).
+    w.write_bits(1, 1);
+    w.write_bits(1, 2);
+
+    let mut matcher = Matcher::new(data);
+    let mut i = 0;
+    while i < data.len() {
+        match matcher.longest_match(i) {
+            Some((length, distance)) => {
+                write_match(&mut w, length, distance);
+                for pos in i..i + length {
+                    matcher.insert(pos);
+                }
+                i += length;
+            }
+            None => {
+                write_literal(&mut w, data[i] as u32);
+                matcher.insert(i);
+                i += 1;
+            }
+        }
+    }
+
+    write_literal(&mut w, 256);
+    w.finish()
+}
+
+pub fn adler32(data: &[u8]) -> u32 {
+    const MOD_ADLER: u32 = 65521;
+    // Largest n such that 255n(n+1)/2 + (n+1)(MOD_ADLER-1) fits in a u32.
+    const NMAX: usize = 5552;
+
+    let (mut a, mut b) = (1u32, 0u32);
+    for chunk in data.chunks(NMAX) {
+        for &byte in chunk {
+            a += byte as u32;
+            b += a;
+        }
+        a %= MOD_ADLER;
+        b %= MOD_ADLER;
+    }
+    (b << 16) | a
+}
+
+#[derive(Default)]
+struct BitWriter {
+    out: Vec<u8>,
+    bit_buf: u64,
+    bit_count: u32,
+}
+
+impl BitWriter {
+    /// Writes `count` bits of `bits`, least significant bit first.
+    fn write_bits(&mut self, bits: u32, count: u32) {
+        self.bit_buf |= (bits as u64) << self.bit_count;
+        self.bit_count += count;
+        while self.bit_count >= 8 {
+            self.out.push(self.bit_buf as u8);
+            self.bit_buf >>= 8;
+            self.bit_count -= 8;
+        }
+    }
+
+    /// Writes a Huffman code, which DEFLATE packs most significant bit first.
+    fn write_code(&mut self, code: u32, len: u32) {
+        self.write_bits(code.reverse_bits() >> (32 - len), len);
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.bit_count > 0 {
+            self.out.push(self.bit_buf as u8);
+        }
+        self.out
+    }
+}
+
+fn write_literal(w: &mut BitWriter, lit: u32) {
+    match lit {
+        0..=143 => w.write_code(0x30 + lit, 8),
+        144..=255 => w.write_code(0x190 + lit - 144, 9),
+        256..=279 => w.write_code(lit - 256, 7),
+        _ => w.write_code(0xc0 + lit - 280, 8),
+    }
+}
+
+fn write_match(w: &mut BitWriter, length: usize, distance: usize) {
+    let li = LENGTH_BASE.partition_point(|&base| base as usize <= length) - 1;
+    write_literal(w, 257 + li as u32);
+    w.write_bits(
+        (length - LENGTH_BASE[li] as usize) as u32,
+        LENGTH_EXTRA[li] as u32,
+    );
+
+    let di = DIST_BASE.partition_point(|&base| base as usize <= distance) - 1;
+    w.write_code(di as u32, 5);
+    w.write_bits(
+        (distance - DIST_BASE[di] as usize) as u32,
+        DIST_EXTRA[di] as u32,
+    );
+}
+
+/// LZ77 match finder over hash chains of 3-byte prefixes.
+struct Matcher<'a> {
+    data: &'a [u8],
+    head: Vec<usize>,
+    prev: Vec<usize>,
+}
+
+impl<'a> Matcher<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self {
+            data,
+            head: vec![NIL; HASH_SIZE],
+            prev: vec![NIL; WINDOW_SIZE],
+        }
+    }
+
+    fn hash(&self, pos: usize) -> usize {
+        let d = &self.data[pos..pos + MIN_MATCH];
+        let v = (d[0] as u32) << 16 | (d[1] as u32) << 8 | d[2] as u32;
+        (v.wrapping_mul(0x9e37_79b1) >> (32 - HASH_BITS)) as usize
+    }
+
+    fn insert(&mut self, pos: usize) {
+        if pos + MIN_MATCH > self.data.len() {
+            return;
+        }
+        let h = self.hash(pos);
+        self.prev[pos % WINDOW_SIZE] = self.head[h];
+        self.head[h] = pos;
+    }
+
+    /// Returns the longest `(length, distance)` match for the bytes at `pos`.
+    fn longest_match(&self, pos: usize) -> Option<(usize, usize)> {
+        if pos + MIN_MATCH > self.data.len() {
+            return None;
+        }
+
+        let max_len = MAX_MATCH.min(self.data.len() - pos);
+        let target = &self.data[pos..pos + max_len];
+
+        let mut best: Option<(usize, usize)> = None;
+        let mut candidate = self.head[self.hash(pos)];
+        for _ in 0..MAX_CHAIN {
+            if candidate == NIL || pos - candidate > WINDOW_SIZE {
+                break;
+            }
+
+            let len = target
+                .iter()
+                .zip(&self.data[candidate..])
+                .take_while(|(a, b)| a == b)
+                .count();
+            if len >= MIN_MATCH && best.is_none_or(|(best_len, _)| len > best_len) {
+                best = Some((len, pos - candidate));
+                if len == max_len {
+                    break;
+                }
+            }
+
+            let next = self.prev[candidate % WINDOW_SIZE];
+            // The slot may have been reused by a newer position; stop there.
+            if next == NIL || next >= candidate {
+                break;
+            }
+            candidate = next;
+        }
+
+        best
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct BitReader<'a> {
+        data: &'a [u8],
+        pos: usize,
+    }
+
+    impl BitReader<'_> {
+        fn bit(&mut self) -> u32 {
+            let bit = (self.data[self.pos / 8] >> (self.pos % 8)) & 1;
+            self.pos += 1;
+            bit as u32
+        }
+
+        /// Reads `count` bits, least significant bit first.
+        fn bits(&mut self, count: u32) -> u32 {
+            (0..count).fold(0, |acc, i| acc | self.bit() << i)
+        }
+
+        /// Reads `count` bits of a Huffman code, most significant bit first.
+        fn code(&mut self, count: u32) -> u32 {
+            (0..count).fold(0, |acc, _| acc << 1 | self.bit())
+        }
+    }
+
+    /// Decodes a single fixed-Huffman block, the only kind [`deflate`] writes.
+    fn inflate_fixed(stream: &[u8]) -> Vec<u8> {
+        let mut r = BitReader {
+            data: stream,
+            pos: 0,
+        };
+        assert_eq!(r.bits(3), 0b011, "expected a final fixed block");
+
+        let mut out = Vec::new();
+        loop {
+            // Codes are 7 to 9 bits long and told apart by their leading bits.
+            let mut sym = r.code(7);
+            if sym <= 0x17 {
+                sym += 256;
+            } else {
+                sym = sym << 1 | r.bit();
+                sym = match sym {
+                    0x30..=0xbf => sym - 0x30,
+                    0xc0..=0xc7 => sym - 0xc0 + 280,
+                    _ => (sym << 1 | r.bit()) - 0x190 + 144,
+                };
+            }
+
+            match sym {
+                0..=255 => out.push(sym as u8),
+                256 => return out,
+                _ => {
+                    let li = (sym - 257) as usize;
+                    let length =
+                        LENGTH_BASE[li] as usize + r.bits(LENGTH_EXTRA[li] as u32) as usize;
+                    let di = r.code(5) as usize;
+                    let distance = DIST_BASE[di] as usize + r.bits(DIST_EXTRA[di] as u32) as usize;
+                    for _ in 0..length {
+                        out.push(out[out.len() - distance]);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn adler32_matches_reference() {
+        assert_eq!(adler32(b""), 1);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+    }
+
+    #[test]
+    fn deflate_literals() {
+        // BFINAL/BTYPE, 'a' as the 8-bit code 0x91 and the 7-bit end of block.
+        assert_eq!(deflate(b"a"), [0x4b, 0x04, 0x00]);
+
+        let data = b"The quick brown fox";
+        assert_eq!(inflate_fixed(&deflate(data)), data);
+    }
+
+    #[test]
+    fn deflate_matches() {
+        let data = b"abcabcabcabcabcabcabcabcabcabc".repeat(20);
+        let compressed = deflate(&data);
+        assert!(compressed.len() < 20, "{} bytes", compressed.len());
+        assert_eq!(inflate_fixed(&compressed), data);
+
+        // Long runs need several maximum-length matches.
+        let zeros = vec![0u8; 1000];
+        assert_eq!(inflate_fixed(&deflate(&zeros)), zeros);
+    }
+
+    #[test]
+    fn zlib_stream_framing() {
+        let data = b"Wikipedia";
+        let stream = zlib_compress(data);
+        assert_eq!(stream[..2], [0x78, 0x01]);
+        // The header check bits make it a multiple of 31.
+        assert_eq!(u16::from_be_bytes([stream[0], stream[1]]) % 31, 0);
+        assert_eq!(stream[stream.len() - 4..], 0x11e6_0398u32.to_be_bytes());
+        assert_eq!(inflate_fixed(&stream[2..stream.len() - 4]), data);
+    }
+}
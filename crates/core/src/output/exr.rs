@@ -0,0 +1,377 @@
+use std::io::Write;
+
+use anyhow::bail;
+
+use super::ImageWriter;
+use crate::film::Film;
+
+const MAGIC: [u8; 4] = [0x76, 0x2f, 0x31, 0x01];
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ExrPixelType {
+    #[default]
+    Half,
+    Float,
+}
+
+impl ExrPixelType {
+    const fn id(self) -> i32 {
+        match self {
+            Self::Half => 1,
+            Self::Float => 2,
+        }
+    }
+
+    const fn size(self) -> usize {
+        match self {
+            Self::Half => 2,
+            Self::Float => 4,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ExrCompression {
+    None,
+    #[default]
+    Rle,
+}
+
+impl ExrCompression {
+    const fn id(self) -> u8 {
+        match self {
+            Self::None => 0,
+            Self::Rle => 1,
+        }
+    }
+}
+
+/// Scanline OpenEXR with linear R, G and B channels.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ExrWriter {
+    pub pixel_type: ExrPixelType,
+    pub compression: ExrCompression,
+}
+
+impl ExrWriter {
+    pub fn new(pixel_type: ExrPixelType, compression: ExrCompression) -> Self {
+        Self {
+            pixel_type,
+            compression,
+        }
+    }
+}
+
+impl ImageWriter for ExrWriter {
+    fn write(&self, film: &Film, out: &mut dyn Write) -> anyhow::Result<()> {
+        if film.width() == 0 || film.height() == 0 {
+            // The data window is inclusive, so it cannot describe an empty image.
+            bail!(
+                "OpenEXR cannot store an empty {}x{} image",
+                film.width(),
+                film.height()
+            );
+        }
+        let (width, height) = (film.width() as i32, film.height() as i32);
+
+        let mut header = Vec::new();
+        header.extend(MAGIC);
+        // Version 2, single-part scanline file.
+        header.extend(2u32.to_le_bytes());
+
+        // Channels must be listed in alphabetical order.
+        let mut channels = Vec::new();
+        for name in [b"B", b"G", b"R"] {
+            channels.extend(name);
+            channels.push(0);
+            channels.extend(self.pixel_type.id().to_le_bytes());
+            // pLinear and three reserved bytes.
+            channels.extend([0; 4]);
+            channels.extend(1i32.to_le_bytes());
+            channels.extend(1i32.to_le_bytes());
+        }
+        channels.push(0);
+        write_attribute(&mut header, "channels", "chlist", &channels);
+
+        write_attribute(
+            &mut header,
+            "compression",
+            "compression",
+            &[self.compression.id()],
+        );
+
+        let window = [0, 0, width - 1, height - 1]
+            .into_iter()
+            .flat_map(i32::to_le_bytes)
+            .collect::<Vec<_>>();
+        write_attribute(&mut header, "dataWindow", "box2i", &window);
+        write_attribute(&mut header, "displayWindow", "box2i", &window);
+        write_attribute(&mut header, "lineOrder", "lineOrder", &[0]);
+        write_attribute(
+            &mut header,
+            "pixelAspectRatio",
+            "float",
+            &1.0f32.to_le_bytes(),
+        );
+        write_attribute(
+            &mut header,
+            "screenWindowCenter",
+            "v2f",
+            &[0.0f32.to_le_bytes(), 0.0f32.to_le_bytes()].concat(),
+        );
+        write_attribute(
+            &mut header,
+            "screenWindowWidth",
+            "float",
+            &1.0f32.to_le_bytes(),
+        );
+        header.push(0);
+
+        // One scanline per chunk for both supported compression methods.
+        let mut chunks = Vec::with_capacity(film.height());
+        for (y, row) in film.rows().enumerate() {
+            let mut raw = Vec::with_capacity(row.len() * 3 * self.pixel_type.size());
+            for channel in [2, 1, 0] {
+                for pixel_color in row {
+                    let value = pixel_color[channel];
+                    match self.pixel_type {
+                        ExrPixelType::Half => raw.extend(f32_to_f16(value).to_le_bytes()),
+                        ExrPixelType::Float => raw.extend(value.to_le_bytes()),
+                    }
+                }
+            }
+
+            let data = match self.compression {
+                ExrCompression::None => raw,
+                ExrCompression::Rle => {
+                    let compressed = rle_compress(&raw);
+                    // Readers treat a chunk that is not smaller as uncompressed.
+                    if compressed.len() < raw.len() {
+                        compressed
+                    } else {
+                        raw
+                    }
+                }
+            };
+
+            let mut chunk = Vec::with_capacity(data.len() + 8);
+            chunk.extend((y as i32).to_le_bytes());
+            chunk.extend((data.len() as i32).to_le_bytes());
+            chunk.extend(data);
+            chunks.push(chunk);
+        }
+
+        let mut offset = (header.len() + chunks.len() * 8) as u64;
+        let mut offsets = Vec::with_capacity(chunks.len() * 8);
+        for chunk in chunks.iter() {
+            offsets.extend(offset.to_le_bytes());
+            offset += chunk.len() as u64;
+        }
+
+        out.write_all(&header)?;
+        out.write_all(&offsets)?;
+        for chunk in chunks.iter() {
+            out.write_all(chunk)?;
+        }
+        Ok(())
+    }
+}
+
+fn write_attribute(header: &mut Vec<u8>, name: &str, kind: &str, value: &[u8]) {
+    header.extend(name.as_bytes());
+    header.push(0);
+    header.extend(kind.as_bytes());
+    header.push(0);
+    header.extend((value.len() as i32).to_le_bytes());
+    header.extend(value);
+}
+
+/// Converts to IEEE 754 half precision, rounding to nearest even.
+fn f32_to_f16(value: f32) -> u16 {
+    let bits = value.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exponent = ((bits >> 23) & 0xff) as i32;
+    let mantissa = bits & 0x007f_ffff;
+
+    if exponent == 0xff {
+        let nan = if mantissa != 0 { 0x0200 } else { 0 };
+        return sign | 0x7c00 | nan;
+    }
+
+    let e = exponent - 127 + 15;
+    if e >= 0x1f {
+        return sign | 0x7c00;
+    }
+
+    let (m, shift) = if e <= 0 {
+        if e < -10 {
+            return sign;
+        }
+        // Subnormal half: shift the implicit leading bit into the mantissa.
+        (mantissa | 0x0080_0000, (14 - e) as u32)
+    } else {
+        (mantissa, 13)
+    };
+
+    let half = (m >> shift) | if e > 0 { (e as u32) << 10 } else { 0 };
+    let round_bit = 1 << (shift - 1);
+    let rounded = if m & round_bit != 0 && m & (3 * round_bit - 1) != 0 {
+        // A carry out of the mantissa correctly bumps the exponent.
+        half + 1
+    } else {
+        half
+    };
+    sign | rounded as u16
+}
+
+/// OpenEXR RLE compression: byte reordering, delta prediction and run-length coding.
+fn rle_compress(raw: &[u8]) -> Vec<u8> {
+    const MIN_RUN_LENGTH: usize = 3;
+    const MAX_RUN_LENGTH: usize = 127;
+
+    // Interleave bytes so that the high and low halves of values end up apart.
+    let half = raw.len().div_ceil(2);
+    let mut tmp = vec![0u8; raw.len()];
+    for (i, &byte) in raw.iter().enumerate() {
+        let idx = if i % 2 == 0 { i / 2 } else { half + i / 2 };
+        tmp[idx] = byte;
+    }
+
+    let mut prev = tmp.first().copied().unwrap_or_default();
+    for byte in tmp.iter_mut().skip(1) {
+        let cur = *byte;
+        *byte = (cur as i32 - prev as i32 + 128 + 256) as u8;
+        prev = cur;
+    }
+
+    let mut out = Vec::with_capacity(tmp.len());
+    let n = tmp.len();
+    let mut runs = 0;
+    let mut run_end = 1;
+    while runs < n {
+        while run_end < n && tmp[runs] == tmp[run_end] && run_end - runs - 1 < MAX_RUN_LENGTH {
+            run_end += 1;
+        }
+
+        if run_end - runs >= MIN_RUN_LENGTH {
+            out.push((run_end - runs - 1) as u8);
+            out.push(tmp[runs]);
+            runs = run_end;
+        } else {
+            while run_end < n
+                && (run_end + 1 >= n
+                    || tmp[run_end] != tmp[run_end + 1]
+                    || run_end + 2 >= n
+                    || tmp[run_end + 1] != tmp[run_end + 2])
+                && run_end - runs < MAX_RUN_LENGTH
+            {
+                run_end += 1;
+            }
+
+            out.push((runs as isize - run_end as isize) as u8);
+            out.extend(&tmp[runs..run_end]);
+            runs = run_end;
+        }
+
+        run_end += 1;
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Inverse of [`rle_compress`], following the OpenEXR reader.
+    fn rle_uncompress(data: &[u8], len: usize) -> Vec<u8> {
+        let mut tmp = Vec::with_capacity(len);
+        let mut i = 0;
+        while i < data.len() {
+            let count = data[i] as i8;
+            if count < 0 {
+                let n = -(count as isize) as usize;
+                tmp.extend(&data[i + 1..i + 1 + n]);
+                i += 1 + n;
+            } else {
+                tmp.extend(std::iter::repeat_n(data[i + 1], count as usize + 1));
+                i += 2;
+            }
+        }
+
+        for k in 1..tmp.len() {
+            tmp[k] = (tmp[k - 1] as i32 + tmp[k] as i32 - 128) as u8;
+        }
+        let half = len.div_ceil(2);
+        (0..len)
+            .map(|k| tmp[if k % 2 == 0 { k / 2 } else { half + k / 2 }])
+            .collect()
+    }
+
+    #[test]
+    fn half_conversion() {
+        assert_eq!(f32_to_f16(0.0), 0x0000);
+        assert_eq!(f32_to_f16(-0.0), 0x8000);
+        assert_eq!(f32_to_f16(1.0), 0x3c00);
+        assert_eq!(f32_to_f16(-2.0), 0xc000);
+        assert_eq!(f32_to_f16(65504.0), 0x7bff);
+        // Halfway to the next power of two, so it rounds up to infinity.
+        assert_eq!(f32_to_f16(65520.0), 0x7c00);
+        assert_eq!(f32_to_f16(f32::INFINITY), 0x7c00);
+        assert_eq!(f32_to_f16(f32::NEG_INFINITY), 0xfc00);
+
+        let nan = f32_to_f16(f32::NAN);
+        assert_eq!(nan & 0x7c00, 0x7c00);
+        assert_ne!(nan & 0x03ff, 0);
+    }
+
+    #[test]
+    fn half_subnormals() {
+        let smallest = 2f32.powi(-24);
+        assert_eq!(f32_to_f16(smallest), 0x0001);
+        assert_eq!(f32_to_f16(-smallest), 0x8001);
+        assert_eq!(f32_to_f16(1023.0 * smallest), 0x03ff);
+        assert_eq!(f32_to_f16(2f32.powi(-14)), 0x0400);
+        assert_eq!(f32_to_f16(2f32.powi(-26)), 0x0000);
+    }
+
+    #[test]
+    fn half_ties_round_to_even() {
+        let ulp = 2f32.powi(-10);
+        assert_eq!(f32_to_f16(1.0 + 0.5 * ulp), 0x3c00);
+        assert_eq!(f32_to_f16(1.0 + 1.5 * ulp), 0x3c02);
+        // Just above the tie rounds up.
+        assert_eq!(f32_to_f16(1.0 + 0.5 * ulp + f32::EPSILON), 0x3c01);
+
+        let smallest = 2f32.powi(-24);
+        assert_eq!(f32_to_f16(0.5 * smallest), 0x0000);
+        assert_eq!(f32_to_f16(1.5 * smallest), 0x0002);
+    }
+
+    #[test]
+    fn rle_runs_and_literals() {
+        // Predicted bytes are 0 then seven 128s: one literal and one run.
+        assert_eq!(rle_compress(&[0; 8]), [255, 0, 6, 128]);
+
+        let raw = [1, 9, 4, 4, 4, 4, 4, 4, 7, 2, 8, 8, 8, 3, 0];
+        assert_eq!(rle_uncompress(&rle_compress(&raw), raw.len()), raw);
+
+        let long = (0..600).map(|i| (i / 200) as u8).collect::<Vec<_>>();
+        assert_eq!(rle_uncompress(&rle_compress(&long), long.len()), long);
+    }
+
+    #[test]
+    fn rejects_empty_films() {
+        let mut out = Vec::new();
+        assert!(
+            ExrWriter::default()
+                .write(&Film::new(0, 0), &mut out)
+                .is_err()
+        );
+        assert!(
+            ExrWriter::default()
+                .write(&Film::new(0, 3), &mut out)
+                .is_err()
+        );
+        assert!(out.is_empty());
+    }
+}
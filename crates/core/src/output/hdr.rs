@@ -0,0 +1,126 @@
+use std::io::Write;
+
+use super::ImageWriter;
+use crate::{film::Film, vec3::Color};
+
+/// Radiance RGBE (`.hdr`), storing the linear radiance with a shared exponent.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct HdrWriter;
+
+impl ImageWriter for HdrWriter {
+    fn write(&self, film: &Film, out: &mut dyn Write) -> anyhow::Result<()> {
+        write!(
+            out,
+            "#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n-Y {} +X {}\n",
+            film.height(),
+            film.width()
+        )?;
+
+        let width = film.width();
+        let mut bytes = Vec::new();
+        for row in film.rows() {
+            let rgbe = row.iter().map(to_rgbe).collect::<Vec<_>>();
+
+            // New-style run-length encoding only works for these widths.
+            if !(8..=0x7fff).contains(&width) {
+                bytes.extend(rgbe.iter().flatten());
+                continue;
+            }
+
+            bytes.extend([2, 2, (width >> 8) as u8, (width & 0xff) as u8]);
+            for channel in 0..4 {
+                let values = rgbe.iter().map(|p| p[channel]).collect::<Vec<_>>();
+                rle_encode(&values, &mut bytes);
+            }
+        }
+        out.write_all(&bytes)?;
+        Ok(())
+    }
+}
+
+fn to_rgbe(color: &Color) -> [u8; 4] {
+    let (r, g, b) = (color.x().max(0.0), color.y().max(0.0), color.z().max(0.0));
+    let v = r.max(g).max(b);
+    if v < 1e-32 {
+        return [0; 4];
+    }
+
+    let (mantissa, exponent) = frexp(v);
+    let scale = mantissa * 256.0 / v;
+    [
+        (r * scale) as u8,
+        (g * scale) as u8,
+        (b * scale) as u8,
+        (exponent + 128) as u8,
+    ]
+}
+
+/// Splits a positive, normal `v` into `mantissa * 2^exponent` with the
+/// mantissa in `[0.5, 1)`.
+fn frexp(v: f32) -> (f32, i32) {
+    let bits = v.to_bits();
+    let exponent = ((bits >> 23) & 0xff) as i32 - 126;
+    let mantissa = f32::from_bits((bits & 0x807f_ffff) | (126 << 23));
+    (mantissa, exponent)
+}
+
+/// Run-length encodes one channel of a scanline, as in Radiance's `fwritecolrs`.
+fn rle_encode(data: &[u8], out: &mut Vec<u8>) {
+    const MIN_RUN: usize = 4;
+    const MAX_COUNT: usize = 127;
+
+    let mut cur = 0;
+    while cur < data.len() {
+        // Find the next run long enough to be worth encoding.
+        let mut beg = cur;
+        let mut run = 0;
+        while beg < data.len() {
+            run = 1;
+            while run < MAX_COUNT && beg + run < data.len() && data[beg + run] == data[beg] {
+                run += 1;
+            }
+            if run >= MIN_RUN {
+                break;
+            }
+            beg += run;
+        }
+
+        // Emit everything before the run as literal dumps.
+        while cur < beg {
+            let count = (beg - cur).min(128);
+            out.push(count as u8);
+            out.extend(&data[cur..cur + count]);
+            cur += count;
+        }
+
+        if run >= MIN_RUN {
+            out.push((128 + run) as u8);
+            out.push(data[beg]);
+            cur += run;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rgbe_shares_the_largest_exponent() {
+        assert_eq!(to_rgbe(&Color::new(1.0, 1.0, 1.0)), [128, 128, 128, 129]);
+        assert_eq!(to_rgbe(&Color::new(0.5, 0.25, 0.0)), [128, 64, 0, 128]);
+        assert_eq!(to_rgbe(&Color::new(0.0, -1.0, 0.0)), [0; 4]);
+    }
+
+    #[test]
+    fn rle_runs_and_dumps() {
+        let mut out = Vec::new();
+        rle_encode(&[1, 2, 3, 5, 5, 5, 5, 5, 6], &mut out);
+        assert_eq!(out, [3, 1, 2, 3, 128 + 5, 5, 1, 6]);
+
+        // Runs shorter than four are dumped as literals.
+        out.clear();
+        rle_encode(&[7, 7, 7, 8], &mut out);
+        assert_eq!(out, [4, 7, 7, 7, 8]);
+    }
+}
@@ -0,0 +1,27 @@
+use std::io::Write;
+
+use super::ImageWriter;
+use crate::film::Film;
+
+/// Portable Float Map, storing the linear radiance as 32-bit floats.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct PfmWriter;
+
+impl ImageWriter for PfmWriter {
+    fn write(&self, film: &Film, out: &mut dyn Write) -> anyhow::Result<()> {
+        // A negative scale marks the data as little-endian.
+        write!(out, "PF\n{} {}\n-1.0\n", film.width(), film.height())?;
+
+        // PFM scanlines run from the bottom of the image to the top.
+        let mut bytes = Vec::with_capacity(film.pixels().len() * 12);
+        for row in film.rows().rev() {
+            for pixel_color in row {
+                bytes.extend(pixel_color.x().to_le_bytes());
+                bytes.extend(pixel_color.y().to_le_bytes());
+                bytes.extend(pixel_color.z().to_le_bytes());
+            }
+        }
+        out.write_all(&bytes)?;
+        Ok(())
+    }
+}
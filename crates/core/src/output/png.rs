@@ -0,0 +1,198 @@
+use std::io::Write;
+
+use anyhow::bail;
+
+use super::{ImageWriter, deflate::zlib_compress};
+use crate::{film::Film, vec3::Wrapper};
+
+const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum PngBitDepth {
+    #[default]
+    Eight,
+    Sixteen,
+}
+
+/// Gamma-encoded RGB PNG with 8 or 16 bits per channel.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct PngWriter {
+    pub bit_depth: PngBitDepth,
+}
+
+impl PngWriter {
+    pub fn new(bit_depth: PngBitDepth) -> Self {
+        Self { bit_depth }
+    }
+}
+
+impl ImageWriter for PngWriter {
+    fn write(&self, film: &Film, out: &mut dyn Write) -> anyhow::Result<()> {
+        if film.width() == 0 || film.height() == 0 {
+            bail!(
+                "PNG cannot store an empty {}x{} image",
+                film.width(),
+                film.height()
+            );
+        }
+
+        let (depth, bytes_per_pixel) = match self.bit_depth {
+            PngBitDepth::Eight => (8u8, 3),
+            PngBitDepth::Sixteen => (16u8, 6),
+        };
+
+        let mut ihdr = Vec::with_capacity(13);
+        ihdr.extend((film.width() as u32).to_be_bytes());
+        ihdr.extend((film.height() as u32).to_be_bytes());
+        // Bit depth, color type (RGB), compression, filter and interlace methods.
+        ihdr.extend([depth, 2, 0, 0, 0]);
+
+        let stride = film.width() * bytes_per_pixel;
+        let mut raw = Vec::with_capacity((stride + 1) * film.height());
+        let mut prev_line = vec![0u8; stride];
+        let mut line = Vec::with_capacity(stride);
+        for row in film.rows() {
+            line.clear();
+            for pixel_color in row {
+                let pixel = Wrapper::new(pixel_color);
+                match self.bit_depth {
+                    PngBitDepth::Eight => line.extend(pixel.to_rgb8()),
+                    PngBitDepth::Sixteen => {
+                        line.extend(pixel.to_rgb16().into_iter().flat_map(u16::to_be_bytes))
+                    }
+                }
+            }
+            filter_scanline(&line, &prev_line, bytes_per_pixel, &mut raw);
+            std::mem::swap(&mut line, &mut prev_line);
+        }
+
+        out.write_all(&SIGNATURE)?;
+        write_chunk(out, b"IHDR", &ihdr)?;
+        write_chunk(out, b"IDAT", &zlib_compress(&raw))?;
+        write_chunk(out, b"IEND", &[])?;
+        Ok(())
+    }
+}
+
+fn write_chunk(out: &mut dyn Write, kind: &[u8; 4], data: &[u8]) -> anyhow::Result<()> {
+    out.write_all(&(data.len() as u32).to_be_bytes())?;
+    out.write_all(kind)?;
+    out.write_all(data)?;
+
+    let crc = crc32(kind.iter().chain(data));
+    out.write_all(&crc.to_be_bytes())?;
+    Ok(())
+}
+
+/// Appends the filtered `line` to `out`, choosing the filter type with the
+/// smallest sum of absolute residuals.
+fn filter_scanline(line: &[u8], prev: &[u8], bpp: usize, out: &mut Vec<u8>) {
+    let left = |i: usize| if i >= bpp { line[i - bpp] } else { 0 };
+    let up_left = |i: usize| if i >= bpp { prev[i - bpp] } else { 0 };
+
+    let filtered = |kind: u8| -> Vec<u8> {
+        line.iter()
+            .enumerate()
+            .map(|(i, &x)| match kind {
+                0 => x,
+                1 => x.wrapping_sub(left(i)),
+                2 => x.wrapping_sub(prev[i]),
+                3 => x.wrapping_sub(((left(i) as u16 + prev[i] as u16) / 2) as u8),
+                _ => x.wrapping_sub(paeth(left(i), prev[i], up_left(i))),
+            })
+            .collect()
+    };
+
+    let cost = |data: &[u8]| -> u64 { data.iter().map(|&b| (b as i8).unsigned_abs() as u64).sum() };
+
+    let (kind, data) = (0..=4u8)
+        .map(|kind| (kind, filtered(kind)))
+        .min_by_key(|(_, data)| cost(data))
+        .expect("there is always at least one filter type");
+
+    out.push(kind);
+    out.extend(data);
+}
+
+fn paeth(a: u8, b: u8, c: u8) -> u8 {
+    let p = a as i16 + b as i16 - c as i16;
+    let pa = (p - a as i16).abs();
+    let pb = (p - b as i16).abs();
+    let pc = (p - c as i16).abs();
+    if pa <= pb && pa <= pc {
+        a
+    } else if pb <= pc {
+        b
+    } else {
+        c
+    }
+}
+
+fn crc32<'a>(data: impl IntoIterator<Item = &'a u8>) -> u32 {
+    const TABLE: [u32; 256] = {
+        let mut table = [0u32; 256];
+        let mut n = 0;
+        while n < 256 {
+            let mut c = n as u32;
+            let mut k = 0;
+            while k < 8 {
+                c = if c & 1 != 0 {
+                    0xedb8_8320 ^ (c >> 1)
+                } else {
+                    c >> 1
+                };
+                k += 1;
+            }
+            table[n] = c;
+            n += 1;
+        }
+        table
+    };
+
+    !data.into_iter().fold(!0u32, |crc, &byte| {
+        TABLE[((crc ^ byte as u32) & 0xff) as usize] ^ (crc >> 8)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vec3::Color;
+
+    #[test]
+    fn crc32_matches_reference() {
+        assert_eq!(crc32(b"IEND"), 0xae42_6082);
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+    }
+
+    #[test]
+    fn writes_chunks_in_order() {
+        let film = Film::from_pixels(2, 1, vec![Color::new(1.0, 0.0, 0.0); 2]);
+        let mut out = Vec::new();
+        PngWriter::default().write(&film, &mut out).unwrap();
+
+        assert_eq!(out[..8], SIGNATURE);
+        assert_eq!(out[12..16], *b"IHDR");
+        assert_eq!(out[16..24], [0, 0, 0, 2, 0, 0, 0, 1]);
+        assert_eq!(
+            out[out.len() - 12..],
+            [0, 0, 0, 0, b'I', b'E', b'N', b'D', 0xae, 0x42, 0x60, 0x82]
+        );
+    }
+
+    #[test]
+    fn rejects_empty_films() {
+        let mut out = Vec::new();
+        assert!(
+            PngWriter::default()
+                .write(&Film::new(0, 0), &mut out)
+                .is_err()
+        );
+        assert!(
+            PngWriter::default()
+                .write(&Film::new(4, 0), &mut out)
+                .is_err()
+        );
+        assert!(out.is_empty());
+    }
+}
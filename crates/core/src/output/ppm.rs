@@ -0,0 +1,39 @@
+use std::io::Write;
+
+use super::ImageWriter;
+use crate::{film::Film, vec3::Wrapper};
+
+/// ASCII PPM (P3), gamma-encoded to 8 bits per channel.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct PpmWriter;
+
+impl ImageWriter for PpmWriter {
+    fn write(&self, film: &Film, out: &mut dyn Write) -> anyhow::Result<()> {
+        writeln!(out, "P3")?;
+        writeln!(out, "{} {}", film.width(), film.height())?;
+        writeln!(out, "255")?;
+
+        for pixel_color in film.pixels() {
+            writeln!(out, "{}", Wrapper::new(pixel_color))?;
+        }
+        Ok(())
+    }
+}
+
+/// Binary PPM (P6), gamma-encoded to 8 bits per channel.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct PpmBinaryWriter;
+
+impl ImageWriter for PpmBinaryWriter {
+    fn write(&self, film: &Film, out: &mut dyn Write) -> anyhow::Result<()> {
+        write!(out, "P6\n{} {}\n255\n", film.width(), film.height())?;
+
+        let bytes = film
+            .pixels()
+            .iter()
+            .flat_map(|pixel_color| Wrapper::new(pixel_color).to_rgb8())
+            .collect::<Vec<_>>();
+        out.write_all(&bytes)?;
+        Ok(())
+    }
+}
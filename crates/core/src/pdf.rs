@@ -0,0 +1,140 @@
+use std::f32::consts::PI;
+
+use crate::{
+    hit::{HitRecord, Hittable},
+    rng,
+    vec3::{Point3, Vector3},
+};
+
+/// A probability density over directions, measured with respect to solid angle.
+pub trait Pdf {
+    fn value(&self, direction: &Vector3) -> f32;
+
+    /// Return a random direction distributed according to this density.
+    fn generate(&self) -> Vector3;
+}
+
+/// Uniform density over the whole sphere of directions.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SpherePdf;
+
+impl Pdf for SpherePdf {
+    fn value(&self, _direction: &Vector3) -> f32 {
+        1.0 / (4.0 * PI)
+    }
+
+    fn generate(&self) -> Vector3 {
+        Vector3::random_unit_vector()
+    }
+}
+
+/// Cosine-weighted density over the hemisphere around `w`.
+#[derive(Debug, Clone, Copy)]
+pub struct CosinePdf {
+    s: Vector3,
+    t: Vector3,
+    w: Vector3,
+}
+
+impl CosinePdf {
+    pub fn new(w: &Vector3) -> Self {
+        let w = w.unit_vector();
+        let (s, t) = w.coordinate_system();
+        Self { s, t, w }
+    }
+
+    /// Maps a point of `[0, 1)^2` onto a direction distributed like [`Pdf::generate`].
+    pub fn sample(&self, u: (f32, f32)) -> Vector3 {
+        let d = Vector3::sample_cosine_direction(u);
+        d[0] * self.s + d[1] * self.t + d[2] * self.w
+    }
+}
+
+impl Pdf for CosinePdf {
+    fn value(&self, direction: &Vector3) -> f32 {
+        let cosine_theta = direction.unit_vector().dot(&self.w);
+        (cosine_theta / PI).max(0.0)
+    }
+
+    fn generate(&self) -> Vector3 {
+        self.sample((rng::random_range(0.0..1.0), rng::random_range(0.0..1.0)))
+    }
+}
+
+/// Directions from `origin` towards the objects, as sampled by [`Hittable::random`].
+pub struct HittablePdf<'a> {
+    objects: &'a dyn Hittable,
+    origin: Point3,
+}
+
+impl<'a> HittablePdf<'a> {
+    pub fn new(objects: &'a dyn Hittable, origin: Point3) -> Self {
+        Self { objects, origin }
+    }
+}
+
+impl Pdf for HittablePdf<'_> {
+    fn value(&self, direction: &Vector3) -> f32 {
+        self.objects.pdf_value(&self.origin, direction)
+    }
+
+    fn generate(&self) -> Vector3 {
+        self.objects.random(&self.origin)
+    }
+}
+
+/// Picks `p0` with probability `weight` and `p1` otherwise.
+pub struct MixturePdf<'a> {
+    p0: &'a dyn Pdf,
+    p1: &'a dyn Pdf,
+    weight: f32,
+}
+
+impl<'a> MixturePdf<'a> {
+    pub fn new(p0: &'a dyn Pdf, p1: &'a dyn Pdf, weight: f32) -> Self {
+        Self {
+            p0,
+            p1,
+            weight: weight.clamp(0.0, 1.0),
+        }
+    }
+
+    /// An even mix of the two densities.
+    pub fn even(p0: &'a dyn Pdf, p1: &'a dyn Pdf) -> Self {
+        Self::new(p0, p1, 0.5)
+    }
+}
+
+impl Pdf for MixturePdf<'_> {
+    fn value(&self, direction: &Vector3) -> f32 {
+        self.weight * self.p0.value(direction) + (1.0 - self.weight) * self.p1.value(direction)
+    }
+
+    fn generate(&self) -> Vector3 {
+        if rng::random_range(0.0..1.0) < self.weight {
+            self.p0.generate()
+        } else {
+            self.p1.generate()
+        }
+    }
+}
+
+/// Veach's power heuristic (beta = 2) weight for a sample drawn from the
+/// strategy with density `pdf_a`, combined with a strategy with density `pdf_b`.
+#[inline(always)]
+pub fn power_heuristic(pdf_a: f32, pdf_b: f32) -> f32 {
+    let a2 = pdf_a * pdf_a;
+    let b2 = pdf_b * pdf_b;
+    if a2 + b2 > 0.0 { a2 / (a2 + b2) } else { 0.0 }
+}
+
+/// Converts a uniform density over a surface of `area` into a density over
+/// directions, for the direction that produced the hit `rec`.
+pub fn area_to_solid_angle(rec: &HitRecord, direction: &Vector3, area: f32) -> f32 {
+    let distance_squared = rec.t * rec.t * direction.length_squared();
+    let cosine = (direction.dot(&rec.geometric_normal) / direction.length()).abs();
+    if cosine <= 0.0 || area <= 0.0 {
+        return 0.0;
+    }
+    distance_squared / (cosine * area)
+}
@@ -0,0 +1,295 @@
+use std::{f32::consts::PI, sync::Arc};
+
+use crate::{
+    aabb::Aabb,
+    hit::{HitRecord, Hittable, HittableList},
+    interval::Interval,
+    material::Material,
+    pdf::area_to_solid_angle,
+    ray::Ray,
+    rng,
+    vec3::{Point3, Vector3},
+};
+
+/// The plane through `q` spanned by `u` and `v`, with helpers to find planar coordinates.
+#[derive(Debug, Clone, Copy)]
+struct Plane {
+    q: Point3,
+    u: Vector3,
+    v: Vector3,
+    w: Vector3,
+    normal: Vector3,
+    d: f32,
+}
+
+impl Plane {
+    fn new(q: Point3, u: Vector3, v: Vector3) -> Self {
+        let n = u.cross(&v);
+        let normal = n.unit_vector();
+        Self {
+            q,
+            u,
+            v,
+            w: n / n.dot(&n),
+            normal,
+            d: normal.dot(&q),
+        }
+    }
+
+    /// Returns the ray parameter and the `(alpha, beta)` coordinates of the hit
+    /// point along `u` and `v`, relative to `q`.
+    fn intersect(&self, r: &Ray, interval: &Interval) -> Option<(f32, f32, f32)> {
+        let denom = self.normal.dot(r.direction());
+        // No hit if the ray is parallel to the plane.
+        if denom.abs() < 1e-8 {
+            return None;
+        }
+
+        let t = (self.d - self.normal.dot(r.origin())) / denom;
+        if !interval.surrounds(t) {
+            return None;
+        }
+
+        let planar_hitpt_vector = r.at(t) - self.q;
+        let alpha = self.w.dot(&planar_hitpt_vector.cross(&self.v));
+        let beta = self.w.dot(&self.u.cross(&planar_hitpt_vector));
+        Some((t, alpha, beta))
+    }
+}
+
+/// A parallelogram with corner `q` and edges `u` and `v`.
+///
+/// The front face is the side `u × v` points to.
+#[derive(Clone)]
+pub struct Quad {
+    plane: Plane,
+    area: f32,
+    bbox: Aabb,
+
+    mat: Arc<dyn Material + Send + Sync + 'static>,
+}
+
+impl Quad {
+    pub fn new(
+        q: Point3,
+        u: Vector3,
+        v: Vector3,
+        mat: Arc<dyn Material + Send + Sync + 'static>,
+    ) -> Self {
+        let bbox_diagonal1 = Aabb::from_points(q, q + u + v);
+        let bbox_diagonal2 = Aabb::from_points(q + u, q + v);
+        let bbox = Aabb::enclosing(&bbox_diagonal1, &bbox_diagonal2).pad_to_minimums();
+
+        Self {
+            plane: Plane::new(q, u, v),
+            area: u.cross(&v).length(),
+            bbox,
+            mat,
+        }
+    }
+}
+
+impl Hittable for Quad {
+    fn hit(&self, r: &Ray, interval: &Interval) -> Option<HitRecord> {
+        let (t, alpha, beta) = self.plane.intersect(r, interval)?;
+
+        const UNIT: Interval = Interval::new(0.0, 1.0);
+        if !UNIT.contains(alpha) || !UNIT.contains(beta) {
+            return None;
+        }
+
+        let normal = self.plane.normal;
+        let mut rec = HitRecord {
+            p: r.at(t),
+            normal,
+            geometric_normal: normal,
+            t,
+            u: alpha,
+            v: beta,
+            dpdu: self.plane.u,
+            dpdv: self.plane.v,
+            front_face: false,
+            mat: self.mat.clone(),
+        };
+        rec.set_face_normal(r, &normal);
+
+        Some(rec)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+
+    fn pdf_value(&self, origin: &Point3, direction: &Vector3) -> f32 {
+        let r = Ray::new(*origin, *direction);
+        self.hit(&r, &Interval::new(0.001, f32::INFINITY))
+            .map_or(0.0, |rec| area_to_solid_angle(&rec, direction, self.area))
+    }
+
+    fn random(&self, origin: &Point3) -> Vector3 {
+        let p = self.plane.q
+            + rng::random_range(0.0..1.0) * self.plane.u
+            + rng::random_range(0.0..1.0) * self.plane.v;
+        p - origin
+    }
+}
+
+/// A flat disk; `u` runs around the rim and `v` from the center outwards.
+#[derive(Clone)]
+pub struct Disk {
+    plane: Plane,
+    radius: f32,
+    bbox: Aabb,
+
+    mat: Arc<dyn Material + Send + Sync + 'static>,
+}
+
+impl Disk {
+    /// The front face is the side `normal` points to.
+    pub fn new(
+        center: Point3,
+        normal: Vector3,
+        radius: f32,
+        mat: Arc<dyn Material + Send + Sync + 'static>,
+    ) -> Self {
+        let radius = radius.max(0.0);
+        let (s, t) = normal.unit_vector().coordinate_system();
+        let (u, v) = (radius * s, radius * t);
+
+        // The disk is bounded by the square with the same center and radius.
+        let bbox = Aabb::enclosing(
+            &Aabb::from_points(center - u - v, center + u + v),
+            &Aabb::from_points(center - u + v, center + u - v),
+        )
+        .pad_to_minimums();
+
+        Self {
+            plane: Plane::new(center, u, v),
+            radius,
+            bbox,
+            mat,
+        }
+    }
+
+    pub fn radius(&self) -> f32 {
+        self.radius
+    }
+}
+
+impl Hittable for Disk {
+    fn hit(&self, r: &Ray, interval: &Interval) -> Option<HitRecord> {
+        let (t, alpha, beta) = self.plane.intersect(r, interval)?;
+
+        let dist = (alpha * alpha + beta * beta).sqrt();
+        if dist > 1.0 {
+            return None;
+        }
+
+        let phi = beta.atan2(alpha);
+        let phi = if phi < 0.0 { phi + 2.0 * PI } else { phi };
+
+        let (axis_u, axis_v) = (self.plane.u, self.plane.v);
+        let (dpdu, dpdv) = if dist > 0.0 {
+            let radial = (alpha * axis_u + beta * axis_v) / dist;
+            let tangent = (alpha * axis_v - beta * axis_u) / dist;
+            (2.0 * PI * dist * tangent, radial)
+        } else {
+            (axis_u, axis_v)
+        };
+
+        let normal = self.plane.normal;
+        let mut rec = HitRecord {
+            p: r.at(t),
+            normal,
+            geometric_normal: normal,
+            t,
+            u: phi / (2.0 * PI),
+            v: dist,
+            dpdu,
+            dpdv,
+            front_face: false,
+            mat: self.mat.clone(),
+        };
+        rec.set_face_normal(r, &normal);
+
+        Some(rec)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+
+    fn pdf_value(&self, origin: &Point3, direction: &Vector3) -> f32 {
+        let r = Ray::new(*origin, *direction);
+        let area = PI * self.radius * self.radius;
+        self.hit(&r, &Interval::new(0.001, f32::INFINITY))
+            .map_or(0.0, |rec| area_to_solid_angle(&rec, direction, area))
+    }
+
+    fn random(&self, origin: &Point3) -> Vector3 {
+        let d = Vector3::random_in_unit_disk();
+        let p = self.plane.q + d[0] * self.plane.u + d[1] * self.plane.v;
+        p - origin
+    }
+}
+
+/// Returns the six outward-facing sides of the box with opposite corners `a` and `b`.
+pub fn make_box(
+    a: Point3,
+    b: Point3,
+    mat: Arc<dyn Material + Send + Sync + 'static>,
+) -> HittableList {
+    let mut sides = HittableList::default();
+
+    let min = Point3::new(a.x().min(*b.x()), a.y().min(*b.y()), a.z().min(*b.z()));
+    let max = Point3::new(a.x().max(*b.x()), a.y().max(*b.y()), a.z().max(*b.z()));
+
+    let dx = Vector3::new(max.x() - min.x(), 0.0, 0.0);
+    let dy = Vector3::new(0.0, max.y() - min.y(), 0.0);
+    let dz = Vector3::new(0.0, 0.0, max.z() - min.z());
+
+    // front
+    sides.add(Arc::new(Quad::new(
+        Point3::new(*min.x(), *min.y(), *max.z()),
+        dx,
+        dy,
+        mat.clone(),
+    )));
+    // right
+    sides.add(Arc::new(Quad::new(
+        Point3::new(*max.x(), *min.y(), *max.z()),
+        -dz,
+        dy,
+        mat.clone(),
+    )));
+    // back
+    sides.add(Arc::new(Quad::new(
+        Point3::new(*max.x(), *min.y(), *min.z()),
+        -dx,
+        dy,
+        mat.clone(),
+    )));
+    // left
+    sides.add(Arc::new(Quad::new(
+        Point3::new(*min.x(), *min.y(), *min.z()),
+        dz,
+        dy,
+        mat.clone(),
+    )));
+    // top
+    sides.add(Arc::new(Quad::new(
+        Point3::new(*min.x(), *max.y(), *max.z()),
+        dx,
+        -dz,
+        mat.clone(),
+    )));
+    // bottom
+    sides.add(Arc::new(Quad::new(
+        Point3::new(*min.x(), *min.y(), *min.z()),
+        dx,
+        dz,
+        mat,
+    )));
+
+    sides
+}
@@ -4,11 +4,20 @@ use crate::vec3::{Point3, Vector3};
 pub struct Ray {
     origin: Point3,
     direction: Vector3,
+    time: f32,
 }
 
 impl Ray {
     pub fn new(origin: Point3, direction: Vector3) -> Self {
-        Self { origin, direction }
+        Self::with_time(origin, direction, 0.0)
+    }
+
+    pub fn with_time(origin: Point3, direction: Vector3, time: f32) -> Self {
+        Self {
+            origin,
+            direction,
+            time,
+        }
     }
 
     pub fn origin(&self) -> &Point3 {
@@ -19,6 +28,10 @@ impl Ray {
         &self.direction
     }
 
+    pub fn time(&self) -> f32 {
+        self.time
+    }
+
     pub fn at(&self, t: f32) -> Point3 {
         self.origin + t * self.direction
     }
@@ -0,0 +1,158 @@
+use std::{
+    cell::RefCell,
+    collections::hash_map::RandomState,
+    hash::BuildHasher,
+    ops::{Range, RangeInclusive},
+};
+
+thread_local! {
+    // Unseeded until the camera picks a stream; random per process otherwise.
+    static RNG: RefCell<SplitMix64> =
+        RefCell::new(SplitMix64::new(RandomState::new().hash_one(0u64)));
+}
+
+/// Restarts this thread's random stream at the one owned by sample `index` of
+/// pixel `(x, y)` under `seed`.
+///
+/// The camera calls this before every pixel sample, so everything drawn from
+/// [`random_range`] while tracing that sample depends only on these values,
+/// not on which thread renders it or what it rendered before.
+pub fn seed_pixel_sample(seed: u64, x: i32, y: i32, index: u32) {
+    let stream = hash(&[seed, x as u64, y as u64, index as u64]);
+    RNG.with_borrow_mut(|rng| *rng = SplitMix64::new(stream));
+}
+
+/// Returns a random value in `range` from this thread's stream.
+pub fn random_range<R: UniformRange>(range: R) -> R::Output {
+    RNG.with_borrow_mut(|rng| rng.random_range(range))
+}
+
+/// SplitMix64-style generator: a Weyl sequence passed through a bit mixer.
+///
+/// Both the stream and the mapping onto ranges are defined here, so seeded
+/// renders and textures stay the same across platforms and dependency updates.
+#[derive(Debug, Clone)]
+pub struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+        mix_bits(self.state)
+    }
+
+    pub fn random_range<R: UniformRange>(&mut self, range: R) -> R::Output {
+        range.sample(self.next_u64())
+    }
+}
+
+/// A range [`SplitMix64`] can draw uniformly distributed values from.
+pub trait UniformRange {
+    type Output;
+
+    /// Maps 64 uniformly random `bits` onto the range.
+    fn sample(&self, bits: u64) -> Self::Output;
+}
+
+impl UniformRange for Range<f32> {
+    type Output = f32;
+
+    fn sample(&self, bits: u64) -> f32 {
+        assert!(self.start < self.end, "cannot sample empty range");
+        // The top 24 bits give every f32 multiple of 2^-24 in [0, 1).
+        let u = (bits >> 40) as f32 * (1.0 / (1u32 << 24) as f32);
+        let v = self.start + (self.end - self.start) * u;
+        // Rounding can reach the excluded end of a wide range.
+        if v < self.end {
+            v
+        } else {
+            self.end.next_down().max(self.start)
+        }
+    }
+}
+
+impl UniformRange for RangeInclusive<f32> {
+    type Output = f32;
+
+    fn sample(&self, bits: u64) -> f32 {
+        let (start, end) = (*self.start(), *self.end());
+        assert!(start <= end, "cannot sample empty range");
+        let u = (bits >> 40) as f32 * (1.0 / ((1u32 << 24) - 1) as f32);
+        (start + (end - start) * u).min(end)
+    }
+}
+
+impl UniformRange for Range<usize> {
+    type Output = usize;
+
+    fn sample(&self, bits: u64) -> usize {
+        assert!(self.start < self.end, "cannot sample empty range");
+        // Widening multiply; the bias is below 2^-32 for any practical length.
+        let len = (self.end - self.start) as u64;
+        self.start + ((bits as u128 * len as u128) >> 64) as usize
+    }
+}
+
+/// SplitMix64 finalizer.
+pub(crate) fn mix_bits(mut v: u64) -> u64 {
+    v ^= v >> 31;
+    v = v.wrapping_mul(0x7fb5d329728ea185);
+    v ^= v >> 27;
+    v = v.wrapping_mul(0x81dadef4bc2dd44d);
+    v ^= v >> 33;
+    v
+}
+
+pub(crate) fn hash(values: &[u64]) -> u64 {
+    values
+        .iter()
+        .fold(0x9e3779b97f4a7c15, |h, &v| mix_bits(h ^ mix_bits(v)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ranges_stay_in_bounds() {
+        for bits in [0, 1, u64::MAX / 2, u64::MAX - 1, u64::MAX] {
+            let v = (0.0f32..1.0).sample(bits);
+            assert!((0.0..1.0).contains(&v));
+            let v = (-1.0f32..=1.0).sample(bits);
+            assert!((-1.0..=1.0).contains(&v));
+            assert!((3..10).sample(bits) < 10);
+            // Wide enough that rounding alone would reach the end.
+            let v = (1.0f32..1e9).sample(bits);
+            assert!((1.0..1e9).contains(&v));
+        }
+        assert_eq!((0.0f32..=2.0).sample(u64::MAX), 2.0);
+        assert_eq!((3..10).sample(0), 3);
+        assert_eq!((3..10).sample(u64::MAX), 9);
+    }
+
+    #[test]
+    fn seeded_streams_are_fixed() {
+        // Changing these values changes every seeded render.
+        let mut rng = SplitMix64::new(42);
+        let first = [rng.next_u64(), rng.next_u64(), rng.next_u64()];
+        assert_eq!(
+            first,
+            [
+                4106638399623157023,
+                13954123520635203034,
+                1214089613710128520
+            ]
+        );
+
+        seed_pixel_sample(7, 1, 2, 3);
+        let a = [random_range(0.0..1.0), random_range(0.0..1.0)];
+        seed_pixel_sample(7, 1, 2, 3);
+        let b = [random_range(0.0..1.0), random_range(0.0..1.0)];
+        assert_eq!(a, b);
+    }
+}
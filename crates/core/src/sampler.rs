@@ -0,0 +1,464 @@
+use crate::rng::{self, hash};
+
+/// Source of the sample values used to place camera rays and choose scattering directions.
+///
+/// Each pixel sample asks for a sequence of 1D and 2D values, its dimensions.
+/// A sampler distributes those values well across the samples of a pixel,
+/// which reduces noise compared with independent random numbers.
+pub trait Sampler {
+    /// Moves to sample `index` of pixel `(x, y)` and rewinds to the first dimension.
+    fn start_pixel_sample(&mut self, x: i32, y: i32, index: u32);
+
+    /// Returns the next dimension as a value in `[0, 1)`.
+    fn get_1d(&mut self) -> f32;
+
+    /// Returns the next two dimensions as a point in `[0, 1)^2`.
+    fn get_2d(&mut self) -> (f32, f32);
+}
+
+/// Which [`Sampler`] the camera creates for each render thread.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum SamplerKind {
+    /// Uniform random values with no correlation between samples.
+    #[default]
+    Independent,
+    /// One jittered sample per stratum, with strata shuffled per dimension.
+    Stratified,
+    /// Halton sequence with per-pixel Owen scrambling.
+    Halton,
+    /// Sobol sequence with hash-based Owen scrambling per pixel.
+    Sobol,
+}
+
+impl SamplerKind {
+    /// Creates a sampler tuned for `samples_per_pixel` samples in every pixel,
+    /// with its scrambling chosen by `seed`.
+    pub fn create(&self, samples_per_pixel: u32, seed: u64) -> Box<dyn Sampler> {
+        match self {
+            Self::Independent => Box::new(IndependentSampler),
+            Self::Stratified => Box::new(StratifiedSampler::new(samples_per_pixel, seed)),
+            Self::Halton => Box::new(HaltonSampler::new(seed)),
+            Self::Sobol => Box::new(SobolSampler::new(seed)),
+        }
+    }
+}
+
+/// Pixel sample currently being generated, shared by the sequence samplers.
+#[derive(Debug, Default, Clone, Copy)]
+struct SampleState {
+    seed: u64,
+    pixel_seed: u64,
+    index: u32,
+    dimension: u32,
+}
+
+impl SampleState {
+    fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            ..Self::default()
+        }
+    }
+
+    fn start(&mut self, x: i32, y: i32, index: u32) {
+        self.pixel_seed = hash(&[self.seed, x as u64, y as u64]);
+        self.index = index;
+        self.dimension = 0;
+    }
+
+    /// Returns the current dimension and its per-pixel seed, then advances by `count`.
+    fn take(&mut self, count: u32) -> (u32, u64) {
+        let dimension = self.dimension;
+        self.dimension += count;
+        (dimension, hash(&[self.pixel_seed, dimension as u64]))
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct IndependentSampler;
+
+impl Sampler for IndependentSampler {
+    fn start_pixel_sample(&mut self, _x: i32, _y: i32, _index: u32) {}
+
+    fn get_1d(&mut self) -> f32 {
+        rng::random_range(0.0..1.0)
+    }
+
+    fn get_2d(&mut self) -> (f32, f32) {
+        (self.get_1d(), self.get_1d())
+    }
+}
+
+/// Jittered stratification: the `n` samples of a pixel each fall in a different
+/// stratum of `[0, 1)` for 1D values, or of a grid of at least `n` cells for 2D.
+///
+/// Samples past the expected count fall back to independent values.
+#[derive(Debug, Clone)]
+pub struct StratifiedSampler {
+    samples_per_pixel: u32,
+    grid_x: u32,
+    grid_y: u32,
+    state: SampleState,
+}
+
+impl StratifiedSampler {
+    pub fn new(samples_per_pixel: u32, seed: u64) -> Self {
+        let samples_per_pixel = samples_per_pixel.max(1);
+        let grid_x = (samples_per_pixel as f32).sqrt().ceil() as u32;
+        let grid_y = samples_per_pixel.div_ceil(grid_x);
+        Self {
+            samples_per_pixel,
+            grid_x,
+            grid_y,
+            state: SampleState::new(seed),
+        }
+    }
+}
+
+impl Sampler for StratifiedSampler {
+    fn start_pixel_sample(&mut self, x: i32, y: i32, index: u32) {
+        self.state.start(x, y, index);
+    }
+
+    fn get_1d(&mut self) -> f32 {
+        let (_, seed) = self.state.take(1);
+        let index = self.state.index;
+        if index >= self.samples_per_pixel {
+            return rng::random_range(0.0..1.0);
+        }
+
+        let stratum = permutation_element(index, self.samples_per_pixel, seed as u32);
+        let jitter: f32 = rng::random_range(0.0..1.0);
+        ((stratum as f32 + jitter) / self.samples_per_pixel as f32).min(ONE_MINUS_EPSILON)
+    }
+
+    fn get_2d(&mut self) -> (f32, f32) {
+        let (_, seed) = self.state.take(2);
+        let index = self.state.index;
+        let strata = self.grid_x * self.grid_y;
+        if index >= strata {
+            return (rng::random_range(0.0..1.0), rng::random_range(0.0..1.0));
+        }
+
+        let stratum = permutation_element(index, strata, seed as u32);
+        let (sx, sy) = (stratum % self.grid_x, stratum / self.grid_x);
+        let (jx, jy): (f32, f32) = (rng::random_range(0.0..1.0), rng::random_range(0.0..1.0));
+        (
+            ((sx as f32 + jx) / self.grid_x as f32).min(ONE_MINUS_EPSILON),
+            ((sy as f32 + jy) / self.grid_y as f32).min(ONE_MINUS_EPSILON),
+        )
+    }
+}
+
+/// Halton sequence, using the radical inverse in the `d`-th prime base for
+/// dimension `d`. Each pixel scrambles the digits with its own Owen
+/// permutation, so neighbouring pixels do not share patterns and the large
+/// bases stay well spread at low sample counts.
+///
+/// Dimensions past the prime table fall back to independent values.
+#[derive(Debug, Default, Clone)]
+pub struct HaltonSampler {
+    state: SampleState,
+}
+
+impl HaltonSampler {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            state: SampleState::new(seed),
+        }
+    }
+}
+
+const PRIMES: [u32; 32] = [
+    2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79, 83, 89, 97,
+    101, 103, 107, 109, 113, 127, 131,
+];
+
+impl Sampler for HaltonSampler {
+    fn start_pixel_sample(&mut self, x: i32, y: i32, index: u32) {
+        self.state.start(x, y, index);
+    }
+
+    fn get_1d(&mut self) -> f32 {
+        let (dimension, seed) = self.state.take(1);
+        let Some(&base) = PRIMES.get(dimension as usize) else {
+            return rng::random_range(0.0..1.0);
+        };
+
+        owen_scrambled_radical_inverse(base, self.state.index, seed)
+    }
+
+    fn get_2d(&mut self) -> (f32, f32) {
+        (self.get_1d(), self.get_1d())
+    }
+}
+
+/// Owen-scrambled Sobol points after Burley, "Practical Hash-based Owen
+/// Scrambling" (2020).
+///
+/// Every 1D or 2D request draws from the first one or two Sobol dimensions
+/// with its own shuffle and scramble seeds, so any number of dimensions stay
+/// well distributed without a large direction-number table.
+#[derive(Debug, Default, Clone)]
+pub struct SobolSampler {
+    state: SampleState,
+}
+
+impl SobolSampler {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            state: SampleState::new(seed),
+        }
+    }
+}
+
+impl Sampler for SobolSampler {
+    fn start_pixel_sample(&mut self, x: i32, y: i32, index: u32) {
+        self.state.start(x, y, index);
+    }
+
+    fn get_1d(&mut self) -> f32 {
+        let (_, seed) = self.state.take(1);
+        let index = nested_uniform_scramble(self.state.index, seed as u32);
+        let x = nested_uniform_scramble(sobol(index, 0), (seed >> 32) as u32);
+        unit_float(x)
+    }
+
+    fn get_2d(&mut self) -> (f32, f32) {
+        let (_, seed) = self.state.take(2);
+        let index = nested_uniform_scramble(self.state.index, seed as u32);
+        let x = nested_uniform_scramble(sobol(index, 0), hash(&[seed, 0]) as u32);
+        let y = nested_uniform_scramble(sobol(index, 1), hash(&[seed, 1]) as u32);
+        (unit_float(x), unit_float(y))
+    }
+}
+
+const ONE_MINUS_EPSILON: f32 = 1.0 - f32::EPSILON / 2.0;
+
+/// Maps the high 24 bits of `x` onto `[0, 1)`.
+fn unit_float(x: u32) -> f32 {
+    (x >> 8) as f32 / (1u32 << 24) as f32
+}
+
+/// Radical inverse of `index` in `base`, with each digit permuted according to
+/// the digits below it.
+fn owen_scrambled_radical_inverse(base: u32, mut index: u32, seed: u64) -> f32 {
+    let inv_base = 1.0 / base as f32;
+    let mut inv_base_m = 1.0f32;
+    let mut reversed_digits = 0u64;
+    let mut position = 0u64;
+    // Stop once further digits no longer change an f32 result.
+    while 1.0 - (base - 1) as f32 * inv_base_m < 1.0 {
+        let digit = index % base;
+        index /= base;
+        // The digits so far only identify the prefix together with their count;
+        // otherwise a leading zero would reuse the permutation of the first digit.
+        let digit_seed = hash(&[seed, reversed_digits, position]);
+        position += 1;
+        let digit = permutation_element(digit, base, digit_seed as u32);
+        reversed_digits = reversed_digits * base as u64 + digit as u64;
+        inv_base_m *= inv_base;
+    }
+    (inv_base_m * reversed_digits as f32).min(ONE_MINUS_EPSILON)
+}
+
+/// Sobol point `index` in dimension 0 (van der Corput) or 1.
+fn sobol(index: u32, dimension: u32) -> u32 {
+    if dimension == 0 {
+        return index.reverse_bits();
+    }
+
+    // Dimension 1 has primitive polynomial x + 1, so v[k+1] = v[k] ^ (v[k] >> 1).
+    let mut v = 1u32 << 31;
+    let mut result = 0;
+    let mut index = index;
+    while index != 0 {
+        if index & 1 != 0 {
+            result ^= v;
+        }
+        index >>= 1;
+        v ^= v >> 1;
+    }
+    result
+}
+
+fn laine_karras_permutation(mut x: u32, seed: u32) -> u32 {
+    x = x.wrapping_add(seed);
+    x ^= x.wrapping_mul(0x6c50b47c);
+    x ^= x.wrapping_mul(0xb82f1e52);
+    x ^= x.wrapping_mul(0xc7afe638);
+    x ^= x.wrapping_mul(0x8d22f6e6);
+    x
+}
+
+/// Owen scrambling: a random permutation of each digit that depends on all higher digits.
+fn nested_uniform_scramble(x: u32, seed: u32) -> u32 {
+    laine_karras_permutation(x.reverse_bits(), seed).reverse_bits()
+}
+
+/// Element `i` of a random permutation of `0..len` chosen by `seed`, after
+/// Kensler, "Correlated Multi-Jittered Sampling" (2013).
+fn permutation_element(mut i: u32, len: u32, seed: u32) -> u32 {
+    let mut w = len - 1;
+    w |= w >> 1;
+    w |= w >> 2;
+    w |= w >> 4;
+    w |= w >> 8;
+    w |= w >> 16;
+    loop {
+        i ^= seed;
+        i = i.wrapping_mul(0xe170893d);
+        i ^= seed >> 16;
+        i ^= (i & w) >> 4;
+        i ^= seed >> 8;
+        i = i.wrapping_mul(0x0929eb3f);
+        i ^= seed >> 23;
+        i ^= (i & w) >> 1;
+        i = i.wrapping_mul(1 | seed >> 27);
+        i = i.wrapping_mul(0x6935fa69);
+        i ^= (i & w) >> 11;
+        i = i.wrapping_mul(0x74dcb303);
+        i ^= (i & w) >> 2;
+        i = i.wrapping_mul(0x9e501cc3);
+        i ^= (i & w) >> 2;
+        i = i.wrapping_mul(0xc860a3df);
+        i &= w;
+        i ^= i >> 5;
+        if i < len {
+            break;
+        }
+    }
+    (i.wrapping_add(seed)) % len
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KINDS: [SamplerKind; 4] = [
+        SamplerKind::Independent,
+        SamplerKind::Stratified,
+        SamplerKind::Halton,
+        SamplerKind::Sobol,
+    ];
+
+    /// The first `dimensions` 1D values of every sample of pixel `(x, y)`.
+    fn values(kind: SamplerKind, spp: u32, (x, y): (i32, i32), dimensions: usize) -> Vec<Vec<f32>> {
+        let mut sampler = kind.create(spp, 3);
+        (0..spp)
+            .map(|index| {
+                rng::seed_pixel_sample(3, x, y, index);
+                sampler.start_pixel_sample(x, y, index);
+                (0..dimensions).map(|_| sampler.get_1d()).collect()
+            })
+            .collect()
+    }
+
+    fn correlation(a: &[f32], b: &[f32]) -> f32 {
+        let n = a.len() as f32;
+        let (ma, mb) = (a.iter().sum::<f32>() / n, b.iter().sum::<f32>() / n);
+        let cov: f32 = a.iter().zip(b).map(|(x, y)| (x - ma) * (y - mb)).sum();
+        let va: f32 = a.iter().map(|x| (x - ma) * (x - ma)).sum();
+        let vb: f32 = b.iter().map(|y| (y - mb) * (y - mb)).sum();
+        cov / (va * vb).sqrt()
+    }
+
+    #[test]
+    fn values_stay_in_the_unit_interval() {
+        for kind in KINDS {
+            let mut sampler = kind.create(16, 1);
+            for index in 0..64 {
+                sampler.start_pixel_sample(index as i32 % 5, 7, index);
+                for _ in 0..40 {
+                    let v = sampler.get_1d();
+                    assert!((0.0..1.0).contains(&v), "{:?}: {}", kind, v);
+                    let (u, v) = sampler.get_2d();
+                    assert!((0.0..1.0).contains(&u) && (0.0..1.0).contains(&v));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn samples_of_a_pixel_fill_distinct_strata() {
+        for kind in [
+            SamplerKind::Stratified,
+            SamplerKind::Halton,
+            SamplerKind::Sobol,
+        ] {
+            let mut sampler = kind.create(16, 5);
+            let mut strata_1d = vec![Vec::new(); 4];
+            let mut cells_2d = Vec::new();
+            for index in 0..16 {
+                sampler.start_pixel_sample(2, 3, index);
+                // Halton only stratifies 16 samples in base 2, its first dimension.
+                let dims = if kind == SamplerKind::Halton { 1 } else { 4 };
+                for strata in strata_1d.iter_mut().take(dims) {
+                    strata.push((sampler.get_1d() * 16.0) as u32);
+                }
+                if kind != SamplerKind::Halton {
+                    let (u, v) = sampler.get_2d();
+                    cells_2d.push(((u * 4.0) as u32, (v * 4.0) as u32));
+                }
+            }
+
+            for strata in strata_1d.iter_mut().filter(|s| !s.is_empty()) {
+                strata.sort();
+                assert_eq!(*strata, (0..16).collect::<Vec<_>>(), "{:?}", kind);
+            }
+            cells_2d.sort();
+            cells_2d.dedup();
+            assert!(cells_2d.is_empty() || cells_2d.len() == 16, "{:?}", kind);
+        }
+
+        // The second Halton dimension, base 3, stratifies 9 samples.
+        let thirds = values(SamplerKind::Halton, 9, (2, 3), 2);
+        let mut strata = thirds
+            .iter()
+            .map(|s| (s[1] * 9.0) as u32)
+            .collect::<Vec<_>>();
+        strata.sort();
+        assert_eq!(strata, (0..9).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn dimensions_and_pixels_are_decorrelated() {
+        let dim = |v: &[Vec<f32>], d: usize| v.iter().map(|s| s[d]).collect::<Vec<_>>();
+
+        for kind in KINDS {
+            // Within a pixel, no dimension follows another.
+            let a = values(kind, 256, (0, 0), 6);
+            for d in 0..6 {
+                for e in d + 1..6 {
+                    let r = correlation(&dim(&a, d), &dim(&a, e));
+                    assert!(
+                        r.abs() < 0.2,
+                        "{:?}: dimensions {} and {}: {}",
+                        kind,
+                        d,
+                        e,
+                        r
+                    );
+                }
+            }
+
+            // The same sample of neighbouring pixels lands in unrelated places.
+            for index in [0, 1, 7] {
+                let row = (0..257)
+                    .map(|x| values(kind, 8, (x, 4), 6).swap_remove(index))
+                    .collect::<Vec<_>>();
+                for d in 0..6 {
+                    let r = correlation(&dim(&row[..256], d), &dim(&row[1..], d));
+                    assert!(
+                        r.abs() < 0.2,
+                        "{:?}: sample {} dimension {}: {}",
+                        kind,
+                        index,
+                        d,
+                        r
+                    );
+                }
+            }
+        }
+    }
+}
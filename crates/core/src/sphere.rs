@@ -1,16 +1,58 @@
-use std::sync::Arc;
+use std::{f32::consts::PI, sync::Arc};
 
 use crate::{
+    aabb::{self, Aabb},
     hit::{HitRecord, Hittable},
     interval::Interval,
     material::Material,
     ray::Ray,
-    vec3::Point3,
+    rng,
+    vec3::{Point3, Vector3},
 };
 
+/// Path followed by a sphere's center over time.
+#[derive(Debug, Clone)]
+enum CenterPath {
+    Static(Point3),
+    /// Moves from `origin` at time 0 to `origin + direction` at time 1.
+    Linear(Ray),
+    /// Piecewise-linear motion through `(time, center)` keys sorted by time.
+    Keyframes(Vec<(f32, Point3)>),
+}
+
+impl CenterPath {
+    /// Times outside the path's range are clamped to its ends.
+    fn at(&self, time: f32) -> Point3 {
+        match self {
+            Self::Static(center) => *center,
+            Self::Linear(path) => path.at(time.clamp(0.0, 1.0)),
+            Self::Keyframes(keys) => {
+                let next = keys.partition_point(|(t, _)| *t <= time);
+                match (next.checked_sub(1).map(|i| keys[i]), keys.get(next)) {
+                    (Some((t0, c0)), Some(&(t1, c1))) => {
+                        let s = (time - t0) / (t1 - t0);
+                        c0 + s * (c1 - c0)
+                    }
+                    (Some((_, c)), None) | (None, Some(&(_, c))) => c,
+                    (None, None) => Point3::default(),
+                }
+            }
+        }
+    }
+
+    /// Positions the center passes through that bound its whole path.
+    fn extremes(&self) -> Vec<Point3> {
+        match self {
+            Self::Static(center) => vec![*center],
+            Self::Linear(path) => vec![path.at(0.0), path.at(1.0)],
+            Self::Keyframes(keys) => keys.iter().map(|(_, c)| *c).collect(),
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct Sphere {
-    center: Point3,
+    center: CenterPath,
     radius: f32,
 
     mat: Arc<dyn Material + Send + Sync + 'static>,
@@ -21,6 +63,35 @@ impl Sphere {
         center: Point3,
         radius: f32,
         mat: Arc<dyn Material + Send + Sync + 'static>,
+    ) -> Self {
+        Self::with_path(CenterPath::Static(center), radius, mat)
+    }
+
+    /// A sphere moving linearly from `center1` at time 0 to `center2` at time 1.
+    pub fn new_moving(
+        center1: Point3,
+        center2: Point3,
+        radius: f32,
+        mat: Arc<dyn Material + Send + Sync + 'static>,
+    ) -> Self {
+        let path = CenterPath::Linear(Ray::new(center1, center2 - center1));
+        Self::with_path(path, radius, mat)
+    }
+
+    /// A sphere moving through `(time, center)` keyframes, given in any order.
+    pub fn new_keyframed(
+        mut keyframes: Vec<(f32, Point3)>,
+        radius: f32,
+        mat: Arc<dyn Material + Send + Sync + 'static>,
+    ) -> Self {
+        keyframes.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Self::with_path(CenterPath::Keyframes(keyframes), radius, mat)
+    }
+
+    fn with_path(
+        center: CenterPath,
+        radius: f32,
+        mat: Arc<dyn Material + Send + Sync + 'static>,
     ) -> Self {
         Self {
             center,
@@ -28,11 +99,34 @@ impl Sphere {
             mat,
         }
     }
+
+    /// Maps a point `p` on the unit sphere to `(u, v)` in `[0, 1]`.
+    ///
+    /// `u` is the angle around the Y axis from X=-1, and `v` is the angle from Y=-1 to Y=+1.
+    pub fn get_sphere_uv(p: &Point3) -> (f32, f32) {
+        let theta = (-p.y()).acos();
+        let phi = (-p.z()).atan2(*p.x()) + PI;
+
+        (phi / (2.0 * PI), theta / PI)
+    }
+
+    /// Returns `(dpdu, dpdv)` at the point with unit outward normal `n`.
+    fn get_sphere_tangents(&self, n: &Vector3) -> (Vector3, Vector3) {
+        let (x, y, z) = (*n.x(), *n.y(), *n.z());
+        // Avoid dividing by zero at the poles, where dpdv is undefined.
+        let sin_theta = (1.0 - y * y).max(0.0).sqrt().max(1e-6);
+
+        let dpdu = 2.0 * PI * self.radius * Vector3::new(z, 0.0, -x);
+        let dpdv =
+            PI * self.radius * Vector3::new(-x * y / sin_theta, sin_theta, -z * y / sin_theta);
+        (dpdu, dpdv)
+    }
 }
 
 impl Hittable for Sphere {
     fn hit(&self, r: &Ray, interval: &Interval) -> Option<HitRecord> {
-        let oc = self.center - r.origin();
+        let center = self.center.at(r.time());
+        let oc = center - r.origin();
 
         let a = r.direction().length_squared();
         let h = r.direction().dot(&oc);
@@ -54,14 +148,19 @@ impl Hittable for Sphere {
 
         let t = root;
         let p = r.at(t);
-        let n = (p - self.center) / self.radius;
-
-        let outward_normal = (p - self.center) / self.radius;
+        let outward_normal = (p - center) / self.radius;
+        let (u, v) = Self::get_sphere_uv(&outward_normal);
+        let (dpdu, dpdv) = self.get_sphere_tangents(&outward_normal);
 
         let mut rec = HitRecord {
             p,
-            normal: n,
+            normal: outward_normal,
+            geometric_normal: outward_normal,
             t,
+            u,
+            v,
+            dpdu,
+            dpdv,
             front_face: false,
             mat: self.mat.clone(),
         };
@@ -69,4 +168,60 @@ impl Hittable for Sphere {
 
         Some(rec)
     }
+
+    fn bounding_box(&self) -> Aabb {
+        let rvec = Vector3::new(self.radius, self.radius, self.radius);
+        self.center
+            .extremes()
+            .into_iter()
+            .map(|center| Aabb::from_points(center - rvec, center + rvec))
+            .fold(aabb::EMPTY, |bbox, b| Aabb::enclosing(&bbox, &b))
+    }
+
+    /// Samples the cone subtended by the sphere; moving spheres use their center at time 0.
+    fn pdf_value(&self, origin: &Point3, direction: &Vector3) -> f32 {
+        let r = Ray::new(*origin, *direction);
+        if self.hit(&r, &Interval::new(0.001, f32::INFINITY)).is_none() {
+            return 0.0;
+        }
+
+        let distance_squared = (self.center.at(0.0) - origin).length_squared();
+        let radius_squared = self.radius * self.radius;
+        if distance_squared <= radius_squared {
+            return 1.0 / (4.0 * PI);
+        }
+
+        // 1 - cos(theta_max), rearranged to stay accurate for small, distant spheres.
+        let x = radius_squared / distance_squared;
+        let one_minus_cos_theta_max = x / (1.0 + (1.0 - x).sqrt());
+        1.0 / (2.0 * PI * one_minus_cos_theta_max)
+    }
+
+    fn random(&self, origin: &Point3) -> Vector3 {
+        let direction = self.center.at(0.0) - origin;
+        let distance_squared = direction.length_squared();
+        let radius_squared = self.radius * self.radius;
+        if distance_squared <= radius_squared {
+            return Vector3::random_unit_vector();
+        }
+
+        let w = direction.unit_vector();
+        let (s, t) = w.coordinate_system();
+        let d = random_to_sphere(radius_squared / distance_squared);
+        d[0] * s + d[1] * t + d[2] * w
+    }
+}
+
+/// Returns a direction around +Z, uniform within the cone subtended by a
+/// sphere with `radius^2 / distance^2 == x`.
+fn random_to_sphere(x: f32) -> Vector3 {
+    let r1: f32 = rng::random_range(0.0..1.0);
+    let r2: f32 = rng::random_range(0.0..1.0);
+
+    let one_minus_cos_theta_max = x / (1.0 + (1.0 - x).sqrt());
+    let z = 1.0 - r2 * one_minus_cos_theta_max;
+
+    let phi = 2.0 * PI * r1;
+    let sin_theta = (1.0 - z * z).max(0.0).sqrt();
+    Vector3::new(phi.cos() * sin_theta, phi.sin() * sin_theta, z)
 }
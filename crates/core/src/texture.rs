@@ -0,0 +1,110 @@
+use std::{path::Path, sync::Arc};
+
+use crate::{
+    film::Film,
+    input,
+    interval::Interval,
+    vec3::{Color, Point3},
+};
+
+pub trait Texture {
+    /// Return the texture color at surface coordinates `(u, v)` and point `p`.
+    fn value(&self, u: f32, v: f32, p: &Point3) -> Color;
+}
+
+#[derive(Debug, Clone)]
+pub struct SolidColor {
+    albedo: Color,
+}
+
+impl SolidColor {
+    pub fn new(albedo: Color) -> Self {
+        Self { albedo }
+    }
+}
+
+impl Texture for SolidColor {
+    fn value(&self, _u: f32, _v: f32, _p: &Point3) -> Color {
+        self.albedo
+    }
+}
+
+/// A 3D checkerboard alternating between two textures in cubes of side `scale`.
+#[derive(Clone)]
+pub struct Checker {
+    inv_scale: f32,
+    even: Arc<dyn Texture + Send + Sync + 'static>,
+    odd: Arc<dyn Texture + Send + Sync + 'static>,
+}
+
+impl Checker {
+    pub fn new(
+        scale: f32,
+        even: Arc<dyn Texture + Send + Sync + 'static>,
+        odd: Arc<dyn Texture + Send + Sync + 'static>,
+    ) -> Self {
+        Self {
+            inv_scale: 1.0 / scale,
+            even,
+            odd,
+        }
+    }
+
+    pub fn from_colors(scale: f32, even: Color, odd: Color) -> Self {
+        Self::new(
+            scale,
+            Arc::new(SolidColor::new(even)),
+            Arc::new(SolidColor::new(odd)),
+        )
+    }
+}
+
+impl Texture for Checker {
+    fn value(&self, u: f32, v: f32, p: &Point3) -> Color {
+        let x = (self.inv_scale * p.x()).floor() as i32;
+        let y = (self.inv_scale * p.y()).floor() as i32;
+        let z = (self.inv_scale * p.z()).floor() as i32;
+
+        if (x + y + z) % 2 == 0 {
+            self.even.value(u, v, p)
+        } else {
+            self.odd.value(u, v, p)
+        }
+    }
+}
+
+/// An image mapped over the `(u, v)` unit square, with nearest-pixel lookup.
+#[derive(Debug, Clone)]
+pub struct ImageTexture {
+    image: Film,
+}
+
+impl ImageTexture {
+    /// Expects `image` to hold linear color values.
+    pub fn new(image: Film) -> Self {
+        Self { image }
+    }
+
+    /// Loads an image file, see [`input::load`] for the supported formats.
+    pub fn load<P: AsRef<Path>>(path: P) -> anyhow::Result<Self> {
+        Ok(Self::new(input::load(path)?))
+    }
+}
+
+impl Texture for ImageTexture {
+    fn value(&self, u: f32, v: f32, _p: &Point3) -> Color {
+        // Solid cyan makes missing texture data easy to spot.
+        if self.image.width() == 0 || self.image.height() == 0 {
+            return Color::new(0.0, 1.0, 1.0);
+        }
+
+        // Image rows run top to bottom, while v grows upwards.
+        const UNIT: Interval = Interval::new(0.0, 1.0);
+        let u = UNIT.clamp(u);
+        let v = 1.0 - UNIT.clamp(v);
+
+        let i = ((u * self.image.width() as f32) as usize).min(self.image.width() - 1);
+        let j = ((v * self.image.height() as f32) as usize).min(self.image.height() - 1);
+        *self.image.get(i, j)
+    }
+}
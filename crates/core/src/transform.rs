@@ -0,0 +1,377 @@
+use core::ops;
+
+use crate::{
+    aabb::{self, Aabb},
+    utils::degrees_to_radians,
+    vec3::{Point3, Vector3},
+};
+
+/// Row-major 4x4 matrix acting on column vectors.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Matrix4 {
+    pub m: [[f32; 4]; 4],
+}
+
+impl Default for Matrix4 {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl Matrix4 {
+    pub const IDENTITY: Self = Self::new([
+        [1.0, 0.0, 0.0, 0.0],
+        [0.0, 1.0, 0.0, 0.0],
+        [0.0, 0.0, 1.0, 0.0],
+        [0.0, 0.0, 0.0, 1.0],
+    ]);
+
+    pub const fn new(m: [[f32; 4]; 4]) -> Self {
+        Self { m }
+    }
+
+    pub fn transpose(&self) -> Self {
+        let mut t = [[0.0; 4]; 4];
+        for (i, row) in t.iter_mut().enumerate() {
+            for (j, v) in row.iter_mut().enumerate() {
+                *v = self.m[j][i];
+            }
+        }
+        Self::new(t)
+    }
+
+    /// Inverts the matrix with Gauss-Jordan elimination, or returns `None` if it is singular.
+    pub fn inverse(&self) -> Option<Self> {
+        let mut a = self.m.map(|row| row.map(|v| v as f64));
+        let mut inv = Self::IDENTITY.m.map(|row| row.map(|v| v as f64));
+
+        for col in 0..4 {
+            let pivot = (col..4).max_by(|&x, &y| a[x][col].abs().total_cmp(&a[y][col].abs()))?;
+            if a[pivot][col].abs() < 1e-12 {
+                return None;
+            }
+            a.swap(col, pivot);
+            inv.swap(col, pivot);
+
+            let scale = 1.0 / a[col][col];
+            for j in 0..4 {
+                a[col][j] *= scale;
+                inv[col][j] *= scale;
+            }
+
+            for row in 0..4 {
+                if row == col {
+                    continue;
+                }
+                let factor = a[row][col];
+                for j in 0..4 {
+                    a[row][j] -= factor * a[col][j];
+                    inv[row][j] -= factor * inv[col][j];
+                }
+            }
+        }
+
+        Some(Self::new(inv.map(|row| row.map(|v| v as f32))))
+    }
+
+    /// Determinant of the upper-left 3x3 block: how much the linear part scales volumes.
+    pub fn linear_determinant(&self) -> f32 {
+        let m = &self.m;
+        m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+            - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+            + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+    }
+
+    pub fn transform_point(&self, p: &Point3) -> Point3 {
+        let m = &self.m;
+        let x = m[0][0] * p.x() + m[0][1] * p.y() + m[0][2] * p.z() + m[0][3];
+        let y = m[1][0] * p.x() + m[1][1] * p.y() + m[1][2] * p.z() + m[1][3];
+        let z = m[2][0] * p.x() + m[2][1] * p.y() + m[2][2] * p.z() + m[2][3];
+        let w = m[3][0] * p.x() + m[3][1] * p.y() + m[3][2] * p.z() + m[3][3];
+
+        if w == 1.0 {
+            Point3::new(x, y, z)
+        } else {
+            Point3::new(x, y, z) / w
+        }
+    }
+
+    pub fn transform_vector(&self, v: &Vector3) -> Vector3 {
+        let m = &self.m;
+        Vector3::new(
+            m[0][0] * v.x() + m[0][1] * v.y() + m[0][2] * v.z(),
+            m[1][0] * v.x() + m[1][1] * v.y() + m[1][2] * v.z(),
+            m[2][0] * v.x() + m[2][1] * v.y() + m[2][2] * v.z(),
+        )
+    }
+}
+
+impl ops::Mul<Matrix4> for Matrix4 {
+    type Output = Matrix4;
+
+    fn mul(self, rhs: Matrix4) -> Self::Output {
+        let mut r = [[0.0; 4]; 4];
+        for (i, row) in r.iter_mut().enumerate() {
+            for (j, v) in row.iter_mut().enumerate() {
+                *v = (0..4).map(|k| self.m[i][k] * rhs.m[k][j]).sum();
+            }
+        }
+        Matrix4::new(r)
+    }
+}
+
+/// An affine transform together with its inverse.
+///
+/// `a * b` applies `b` first and then `a`; [`Transform::then`] reads in application order.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Transform {
+    m: Matrix4,
+    m_inv: Matrix4,
+}
+
+impl Transform {
+    pub const IDENTITY: Self = Self {
+        m: Matrix4::IDENTITY,
+        m_inv: Matrix4::IDENTITY,
+    };
+
+    /// Returns `None` if `m` is not invertible.
+    pub fn from_matrix(m: Matrix4) -> Option<Self> {
+        Some(Self {
+            m,
+            m_inv: m.inverse()?,
+        })
+    }
+
+    pub fn translate(offset: &Vector3) -> Self {
+        let (x, y, z) = (*offset.x(), *offset.y(), *offset.z());
+        Self {
+            m: Matrix4::new([
+                [1.0, 0.0, 0.0, x],
+                [0.0, 1.0, 0.0, y],
+                [0.0, 0.0, 1.0, z],
+                [0.0, 0.0, 0.0, 1.0],
+            ]),
+            m_inv: Matrix4::new([
+                [1.0, 0.0, 0.0, -x],
+                [0.0, 1.0, 0.0, -y],
+                [0.0, 0.0, 1.0, -z],
+                [0.0, 0.0, 0.0, 1.0],
+            ]),
+        }
+    }
+
+    /// Non-uniform scale; every factor must be non-zero.
+    pub fn scale(x: f32, y: f32, z: f32) -> Self {
+        Self {
+            m: Matrix4::new([
+                [x, 0.0, 0.0, 0.0],
+                [0.0, y, 0.0, 0.0],
+                [0.0, 0.0, z, 0.0],
+                [0.0, 0.0, 0.0, 1.0],
+            ]),
+            m_inv: Matrix4::new([
+                [1.0 / x, 0.0, 0.0, 0.0],
+                [0.0, 1.0 / y, 0.0, 0.0],
+                [0.0, 0.0, 1.0 / z, 0.0],
+                [0.0, 0.0, 0.0, 1.0],
+            ]),
+        }
+    }
+
+    /// Rotation by `degrees` counter-clockwise around `axis`.
+    pub fn rotate(axis: &Vector3, degrees: f32) -> Self {
+        let a = axis.unit_vector();
+        let (x, y, z) = (*a.x(), *a.y(), *a.z());
+        let theta = degrees_to_radians(degrees);
+        let (sin_theta, cos_theta) = theta.sin_cos();
+        let t = 1.0 - cos_theta;
+
+        let m = Matrix4::new([
+            [
+                t * x * x + cos_theta,
+                t * x * y - sin_theta * z,
+                t * x * z + sin_theta * y,
+                0.0,
+            ],
+            [
+                t * x * y + sin_theta * z,
+                t * y * y + cos_theta,
+                t * y * z - sin_theta * x,
+                0.0,
+            ],
+            [
+                t * x * z - sin_theta * y,
+                t * y * z + sin_theta * x,
+                t * z * z + cos_theta,
+                0.0,
+            ],
+            [0.0, 0.0, 0.0, 1.0],
+        ]);
+
+        // Rotations are orthogonal, so the inverse is the transpose.
+        Self {
+            m,
+            m_inv: m.transpose(),
+        }
+    }
+
+    pub fn rotate_x(degrees: f32) -> Self {
+        Self::rotate(&Vector3::new(1.0, 0.0, 0.0), degrees)
+    }
+
+    pub fn rotate_y(degrees: f32) -> Self {
+        Self::rotate(&Vector3::new(0.0, 1.0, 0.0), degrees)
+    }
+
+    pub fn rotate_z(degrees: f32) -> Self {
+        Self::rotate(&Vector3::new(0.0, 0.0, 1.0), degrees)
+    }
+
+    /// Returns the transform that applies `self` and then `next`.
+    pub fn then(self, next: Transform) -> Self {
+        next * self
+    }
+
+    pub fn inverse(&self) -> Self {
+        Self {
+            m: self.m_inv,
+            m_inv: self.m,
+        }
+    }
+
+    pub const fn matrix(&self) -> &Matrix4 {
+        &self.m
+    }
+
+    pub const fn inverse_matrix(&self) -> &Matrix4 {
+        &self.m_inv
+    }
+
+    pub fn point(&self, p: &Point3) -> Point3 {
+        self.m.transform_point(p)
+    }
+
+    pub fn vector(&self, v: &Vector3) -> Vector3 {
+        self.m.transform_vector(v)
+    }
+
+    /// Transforms a surface normal with the inverse transpose; the result is not normalized.
+    pub fn normal(&self, n: &Vector3) -> Vector3 {
+        self.m_inv.transpose().transform_vector(n)
+    }
+
+    /// Returns the box enclosing all eight transformed corners of `bbox`.
+    pub fn bbox(&self, bbox: &Aabb) -> Aabb {
+        let mut result = aabb::EMPTY;
+        for i in 0..8 {
+            let corner = Point3::new(
+                if i & 1 == 0 { bbox.x.min } else { bbox.x.max },
+                if i & 2 == 0 { bbox.y.min } else { bbox.y.max },
+                if i & 4 == 0 { bbox.z.min } else { bbox.z.max },
+            );
+            let p = self.point(&corner);
+            result = Aabb::enclosing(&result, &Aabb::from_points(p, p));
+        }
+        result
+    }
+}
+
+impl ops::Mul<Transform> for Transform {
+    type Output = Transform;
+
+    fn mul(self, rhs: Transform) -> Self::Output {
+        Transform {
+            m: self.m * rhs.m,
+            m_inv: rhs.m_inv * self.m_inv,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: &Vector3, b: &Vector3) {
+        assert!((a - b).length() < 1e-5, "{:?} vs {:?}", a, b);
+    }
+
+    fn assert_identity(m: &Matrix4) {
+        for (i, row) in m.m.iter().enumerate() {
+            for (j, &v) in row.iter().enumerate() {
+                let expected = if i == j { 1.0 } else { 0.0 };
+                assert!((v - expected).abs() < 1e-5, "{:?}", m);
+            }
+        }
+    }
+
+    #[test]
+    fn inverse_undoes_the_matrix() {
+        let m = Matrix4::new([
+            [2.0, 0.5, 0.0, 1.0],
+            [0.0, 1.0, 3.0, -2.0],
+            [1.0, 0.0, 4.0, 0.5],
+            [0.0, 0.0, 0.0, 1.0],
+        ]);
+        let inv = m.inverse().unwrap();
+        assert_identity(&(inv * m));
+        assert_identity(&(m * inv));
+
+        let singular = Matrix4::new([
+            [1.0, 2.0, 3.0, 0.0],
+            [2.0, 4.0, 6.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ]);
+        assert!(singular.inverse().is_none());
+    }
+
+    #[test]
+    fn stored_inverses_match() {
+        let t = Transform::scale(2.0, 3.0, 0.5)
+            .then(Transform::rotate(&Vector3::new(1.0, 1.0, 0.0), 40.0))
+            .then(Transform::translate(&Vector3::new(1.0, -2.0, 3.0)));
+        assert_identity(&(*t.inverse_matrix() * *t.matrix()));
+        assert_identity(&(t.matrix().inverse().unwrap() * *t.matrix()));
+        assert!((t.matrix().linear_determinant() - 3.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn then_applies_self_first() {
+        let p = Point3::new(1.0, 0.0, 0.0);
+        let scale_then_move = Transform::scale(2.0, 2.0, 2.0)
+            .then(Transform::translate(&Vector3::new(0.0, 1.0, 0.0)));
+        assert_close(&scale_then_move.point(&p), &Point3::new(2.0, 1.0, 0.0));
+
+        let move_then_scale = Transform::translate(&Vector3::new(0.0, 1.0, 0.0))
+            .then(Transform::scale(2.0, 2.0, 2.0));
+        assert_close(&move_then_scale.point(&p), &Point3::new(2.0, 2.0, 0.0));
+
+        // `a * b` applies `b` first.
+        let rotate = Transform::rotate_z(90.0);
+        let shift = Transform::translate(&Vector3::new(1.0, 0.0, 0.0));
+        assert_eq!(rotate.then(shift), shift * rotate);
+        assert_close(&rotate.then(shift).point(&p), &Point3::new(1.0, 1.0, 0.0));
+    }
+
+    #[test]
+    fn vectors_ignore_translation() {
+        let t = Transform::translate(&Vector3::new(5.0, 6.0, 7.0));
+        let v = Vector3::new(1.0, 2.0, 3.0);
+        assert_close(&t.vector(&v), &v);
+        assert_close(&t.inverse().point(&t.point(&v)), &v);
+    }
+
+    #[test]
+    fn normals_stay_perpendicular_under_non_uniform_scale() {
+        // The plane x + y = 0 has normal (1, 1, 0); stretching x flattens it.
+        let t = Transform::scale(4.0, 1.0, 1.0);
+        let tangent = t.vector(&Vector3::new(1.0, -1.0, 0.0));
+        let normal = t.normal(&Vector3::new(1.0, 1.0, 0.0));
+        assert!(tangent.dot(&normal).abs() < 1e-6);
+        assert_close(
+            &normal.unit_vector(),
+            &Vector3::new(0.25, 1.0, 0.0).unit_vector(),
+        );
+    }
+}
@@ -0,0 +1,661 @@
+use std::sync::Arc;
+
+use anyhow::ensure;
+
+use crate::{
+    aabb::Aabb,
+    bvh::BvhNode,
+    hit::{HitRecord, Hittable},
+    interval::Interval,
+    material::Material,
+    pdf::area_to_solid_angle,
+    ray::Ray,
+    rng,
+    vec3::{Point3, Vector3},
+};
+
+#[derive(Clone)]
+pub struct Triangle {
+    vertices: [Point3; 3],
+    normals: Option<[Vector3; 3]>,
+    uvs: Option<[(f32, f32); 3]>,
+
+    mat: Arc<dyn Material + Send + Sync + 'static>,
+}
+
+impl Triangle {
+    /// Vertices are expected in counter-clockwise order when seen from the front.
+    pub fn new(
+        a: Point3,
+        b: Point3,
+        c: Point3,
+        mat: Arc<dyn Material + Send + Sync + 'static>,
+    ) -> Self {
+        Self {
+            vertices: [a, b, c],
+            normals: None,
+            uvs: None,
+            mat,
+        }
+    }
+
+    /// Sets per-vertex shading normals.
+    pub fn with_normals(mut self, normals: [Vector3; 3]) -> Self {
+        self.normals = Some(normals.map(|n| n.unit_vector()));
+        self
+    }
+
+    /// Sets per-vertex texture coordinates.
+    pub fn with_uvs(mut self, uvs: [(f32, f32); 3]) -> Self {
+        self.uvs = Some(uvs);
+        self
+    }
+}
+
+impl Hittable for Triangle {
+    fn hit(&self, r: &Ray, interval: &Interval) -> Option<HitRecord> {
+        let (t, b) = intersect(r, interval, &self.vertices)?;
+        Some(make_record(
+            r,
+            t,
+            b,
+            &self.vertices,
+            self.normals.as_ref(),
+            self.uvs.as_ref(),
+            &self.mat,
+        ))
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        triangle_bbox(&self.vertices)
+    }
+
+    fn pdf_value(&self, origin: &Point3, direction: &Vector3) -> f32 {
+        let r = Ray::new(*origin, *direction);
+        self.hit(&r, &Interval::new(0.001, f32::INFINITY))
+            .map_or(0.0, |rec| {
+                area_to_solid_angle(&rec, direction, triangle_area(&self.vertices))
+            })
+    }
+
+    fn random(&self, origin: &Point3) -> Vector3 {
+        sample_triangle(&self.vertices) - origin
+    }
+}
+
+/// Vertex data shared by all triangles of a mesh.
+struct MeshData {
+    positions: Vec<Point3>,
+    normals: Option<Vec<Vector3>>,
+    uvs: Option<Vec<(f32, f32)>>,
+    indices: Vec<[usize; 3]>,
+
+    mat: Arc<dyn Material + Send + Sync + 'static>,
+}
+
+impl MeshData {
+    fn vertices(&self, face: usize) -> [Point3; 3] {
+        self.indices[face].map(|i| self.positions[i])
+    }
+}
+
+/// A single face of a [`TriangleMesh`], referencing the shared vertex data.
+struct MeshTriangle {
+    mesh: Arc<MeshData>,
+    face: usize,
+}
+
+impl Hittable for MeshTriangle {
+    fn hit(&self, r: &Ray, interval: &Interval) -> Option<HitRecord> {
+        let vertices = self.mesh.vertices(self.face);
+        let (t, b) = intersect(r, interval, &vertices)?;
+
+        let idx = self.mesh.indices[self.face];
+        let normals = self.mesh.normals.as_ref().map(|n| idx.map(|i| n[i]));
+        let uvs = self.mesh.uvs.as_ref().map(|uv| idx.map(|i| uv[i]));
+
+        Some(make_record(
+            r,
+            t,
+            b,
+            &vertices,
+            normals.as_ref(),
+            uvs.as_ref(),
+            &self.mesh.mat,
+        ))
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        triangle_bbox(&self.mesh.vertices(self.face))
+    }
+}
+
+/// An indexed triangle mesh with optional per-vertex normals and UVs.
+///
+/// As a light it is sampled uniformly by area.
+#[derive(Clone)]
+pub struct TriangleMesh {
+    bvh: BvhNode,
+    mesh: Arc<MeshData>,
+    /// Running total of the face areas, for picking a face by area.
+    cumulative_areas: Vec<f32>,
+}
+
+impl TriangleMesh {
+    /// `normals` and `uvs`, when present, are indexed like `positions`.
+    pub fn new(
+        positions: Vec<Point3>,
+        normals: Option<Vec<Vector3>>,
+        uvs: Option<Vec<(f32, f32)>>,
+        indices: Vec<[usize; 3]>,
+        mat: Arc<dyn Material + Send + Sync + 'static>,
+    ) -> anyhow::Result<Self> {
+        if let Some(normals) = normals.as_ref() {
+            ensure!(
+                normals.len() == positions.len(),
+                "mesh has {} normals for {} vertices",
+                normals.len(),
+                positions.len()
+            );
+        }
+        if let Some(uvs) = uvs.as_ref() {
+            ensure!(
+                uvs.len() == positions.len(),
+                "mesh has {} uvs for {} vertices",
+                uvs.len(),
+                positions.len()
+            );
+        }
+        if let Some(i) = indices.iter().flatten().find(|&&i| i >= positions.len()) {
+            anyhow::bail!(
+                "mesh index {} is out of range for {} vertices",
+                i,
+                positions.len()
+            );
+        }
+
+        let triangle_count = indices.len();
+        let cumulative_areas = indices
+            .iter()
+            .scan(0.0, |total, idx| {
+                *total += triangle_area(&idx.map(|i| positions[i]));
+                Some(*total)
+            })
+            .collect();
+        let mesh = Arc::new(MeshData {
+            positions,
+            normals: normals.map(|n| n.iter().map(Vector3::unit_vector).collect()),
+            uvs,
+            indices,
+            mat,
+        });
+
+        let mut triangles = (0..triangle_count)
+            .map(|face| {
+                Arc::new(MeshTriangle {
+                    mesh: mesh.clone(),
+                    face,
+                }) as Arc<dyn Hittable + Send + Sync + 'static>
+            })
+            .collect::<Vec<_>>();
+
+        Ok(Self {
+            bvh: BvhNode::from_objects(&mut triangles),
+            mesh,
+            cumulative_areas,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.mesh.indices.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.mesh.indices.is_empty()
+    }
+
+    fn area(&self) -> f32 {
+        self.cumulative_areas.last().copied().unwrap_or(0.0)
+    }
+}
+
+impl Hittable for TriangleMesh {
+    fn hit(&self, r: &Ray, interval: &Interval) -> Option<HitRecord> {
+        self.bvh.hit(r, interval)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bvh.bounding_box()
+    }
+
+    /// Sums the density of every face the ray crosses, since a direction can
+    /// reach a non-convex mesh more than once.
+    fn pdf_value(&self, origin: &Point3, direction: &Vector3) -> f32 {
+        let area = self.area();
+        if area <= 0.0 {
+            return 0.0;
+        }
+
+        let r = Ray::new(*origin, *direction);
+        let mut pdf = 0.0;
+        let mut t_min = 0.001;
+        while let Some(rec) = self.hit(&r, &Interval::new(t_min, f32::INFINITY)) {
+            pdf += area_to_solid_angle(&rec, direction, area);
+            t_min = rec.t;
+        }
+        pdf
+    }
+
+    fn random(&self, origin: &Point3) -> Vector3 {
+        if self.area() <= 0.0 {
+            return Vector3::new(1.0, 0.0, 0.0);
+        }
+
+        let target = rng::random_range(0.0..self.area());
+        let face = self
+            .cumulative_areas
+            .partition_point(|&total| total <= target)
+            .min(self.cumulative_areas.len() - 1);
+        sample_triangle(&self.mesh.vertices(face)) - origin
+    }
+}
+
+fn triangle_area(vertices: &[Point3; 3]) -> f32 {
+    let [p0, p1, p2] = vertices;
+    0.5 * (p1 - p0).cross(&(p2 - p0)).length()
+}
+
+/// Picks a point uniformly by area (Osada et al.).
+fn sample_triangle(vertices: &[Point3; 3]) -> Point3 {
+    let su0 = rng::random_range(0.0f32..1.0).sqrt();
+    let b0 = 1.0 - su0;
+    let b1 = rng::random_range(0.0..1.0) * su0;
+
+    let [p0, p1, p2] = vertices;
+    b0 * p0 + b1 * p1 + (1.0 - b0 - b1) * p2
+}
+
+fn triangle_bbox(vertices: &[Point3; 3]) -> Aabb {
+    let [a, b, c] = vertices;
+    Aabb::enclosing(&Aabb::from_points(*a, *b), &Aabb::from_points(*c, *c)).pad_to_minimums()
+}
+
+/// Watertight ray/triangle intersection (Woop, Benthin and Wald, 2013).
+///
+/// Returns the ray parameter and the barycentric coordinates of the hit.
+fn intersect(r: &Ray, interval: &Interval, vertices: &[Point3; 3]) -> Option<(f32, [f32; 3])> {
+    let dir = r.direction();
+
+    // Permute so that the ray direction's largest component becomes z.
+    let kz = (0..3)
+        .max_by(|&a, &b| dir[a].abs().total_cmp(&dir[b].abs()))
+        .unwrap_or(2);
+    let kx = (kz + 1) % 3;
+    let ky = (kx + 1) % 3;
+
+    let sx = -dir[kx] / dir[kz];
+    let sy = -dir[ky] / dir[kz];
+    let sz = 1.0 / dir[kz];
+
+    // Translate to the ray origin, permute, and shear onto the +z axis.
+    let [p0, p1, p2] = vertices.map(|p| {
+        let p = p - r.origin();
+        Point3::new(p[kx] + sx * p[kz], p[ky] + sy * p[kz], p[kz])
+    });
+
+    let mut e0 = p1.x() * p2.y() - p1.y() * p2.x();
+    let mut e1 = p2.x() * p0.y() - p2.y() * p0.x();
+    let mut e2 = p0.x() * p1.y() - p0.y() * p1.x();
+
+    // Fall back to double precision when an edge test is exactly zero.
+    if e0 == 0.0 || e1 == 0.0 || e2 == 0.0 {
+        let edge = |a: &Point3, b: &Point3| {
+            (*a.x() as f64 * *b.y() as f64 - *a.y() as f64 * *b.x() as f64) as f32
+        };
+        e0 = edge(&p1, &p2);
+        e1 = edge(&p2, &p0);
+        e2 = edge(&p0, &p1);
+    }
+
+    if (e0 < 0.0 || e1 < 0.0 || e2 < 0.0) && (e0 > 0.0 || e1 > 0.0 || e2 > 0.0) {
+        return None;
+    }
+    let det = e0 + e1 + e2;
+    if det == 0.0 {
+        return None;
+    }
+
+    // A ray through an edge shared by two triangles would hit both; only the
+    // triangle on a fixed side of the edge keeps it.
+    if (e0 == 0.0 && !owns_edge(&p1, &p2, &p0))
+        || (e1 == 0.0 && !owns_edge(&p2, &p0, &p1))
+        || (e2 == 0.0 && !owns_edge(&p0, &p1, &p2))
+    {
+        return None;
+    }
+
+    let t_scaled = (e0 * p0.z() + e1 * p1.z() + e2 * p2.z()) * sz;
+    let t = t_scaled / det;
+    if !interval.surrounds(t) {
+        return None;
+    }
+
+    let inv_det = 1.0 / det;
+    Some((t, [e0 * inv_det, e1 * inv_det, e2 * inv_det]))
+}
+
+/// Whether the triangle with edge `a`-`b` and opposite vertex `c`, in the
+/// sheared 2D space of [`intersect`], owns points exactly on that edge.
+///
+/// The edge is oriented the same way whatever the winding, and the triangle
+/// owns it when `c` lies to its left, so of two triangles on opposite sides of
+/// a shared edge exactly one does.
+fn owns_edge(a: &Point3, b: &Point3, c: &Point3) -> bool {
+    let (mut dx, mut dy) = (*b.x() as f64 - *a.x() as f64, *b.y() as f64 - *a.y() as f64);
+    if dy < 0.0 || (dy == 0.0 && dx < 0.0) {
+        (dx, dy) = (-dx, -dy);
+    }
+    dx * (*c.y() as f64 - *a.y() as f64) - dy * (*c.x() as f64 - *a.x() as f64) > 0.0
+}
+
+fn make_record(
+    r: &Ray,
+    t: f32,
+    b: [f32; 3],
+    vertices: &[Point3; 3],
+    normals: Option<&[Vector3; 3]>,
+    uvs: Option<&[(f32, f32); 3]>,
+    mat: &Arc<dyn Material + Send + Sync + 'static>,
+) -> HitRecord {
+    let [p0, p1, p2] = vertices;
+    let p = b[0] * p0 + b[1] * p1 + b[2] * p2;
+
+    let (u, v) = match uvs {
+        Some([uv0, uv1, uv2]) => (
+            b[0] * uv0.0 + b[1] * uv1.0 + b[2] * uv2.0,
+            b[0] * uv0.1 + b[1] * uv1.1 + b[2] * uv2.1,
+        ),
+        None => (b[1], b[2]),
+    };
+
+    let outward_normal = (p1 - p0).cross(&(p2 - p0)).unit_vector();
+    let (dpdu, dpdv) = triangle_tangents(vertices, uvs, &outward_normal);
+
+    let mut rec = HitRecord {
+        p,
+        normal: outward_normal,
+        geometric_normal: outward_normal,
+        t,
+        u,
+        v,
+        dpdu,
+        dpdv,
+        front_face: false,
+        mat: mat.clone(),
+    };
+    rec.set_face_normal(r, &outward_normal);
+
+    if let Some([n0, n1, n2]) = normals {
+        let shading = (b[0] * n0 + b[1] * n1 + b[2] * n2).unit_vector();
+        rec.set_shading_normal(&shading);
+    }
+
+    rec
+}
+
+/// Solves for `(dpdu, dpdv)` from the vertex positions and UVs.
+fn triangle_tangents(
+    vertices: &[Point3; 3],
+    uvs: Option<&[(f32, f32); 3]>,
+    normal: &Vector3,
+) -> (Vector3, Vector3) {
+    // Matches the default (u, v) = (b1, b2) parameterization.
+    let [uv0, uv1, uv2] = uvs.copied().unwrap_or([(0.0, 0.0), (1.0, 0.0), (0.0, 1.0)]);
+    let [p0, p1, p2] = vertices;
+
+    let duv02 = (uv0.0 - uv2.0, uv0.1 - uv2.1);
+    let duv12 = (uv1.0 - uv2.0, uv1.1 - uv2.1);
+    let dp02 = p0 - p2;
+    let dp12 = p1 - p2;
+
+    let det = duv02.0 * duv12.1 - duv02.1 * duv12.0;
+    if det.abs() < 1e-12 {
+        // Degenerate UVs: any basis of the tangent plane will do.
+        return normal.coordinate_system();
+    }
+
+    let inv_det = 1.0 / det;
+    let dpdu = (duv12.1 * dp02 - duv02.1 * dp12) * inv_det;
+    let dpdv = (duv02.0 * dp12 - duv12.0 * dp02) * inv_det;
+    (dpdu, dpdv)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{material::DiffuseLight, quad::Quad, vec3::Color};
+
+    fn light() -> Arc<dyn Material + Send + Sync> {
+        Arc::new(DiffuseLight::new(Color::new(1.0, 1.0, 1.0)))
+    }
+
+    fn any_t() -> Interval {
+        Interval::new(0.001, f32::INFINITY)
+    }
+
+    fn assert_close(a: Vector3, b: Vector3) {
+        assert!((a - b).length() < 1e-5, "{:?} vs {:?}", a, b);
+    }
+
+    #[test]
+    fn hits_from_both_faces() {
+        let tri = Triangle::new(
+            Point3::new(0.0, 0.0, 0.0),
+            Point3::new(1.0, 0.0, 0.0),
+            Point3::new(0.0, 1.0, 0.0),
+            light(),
+        );
+
+        let front = Ray::new(Point3::new(0.25, 0.25, 2.0), Vector3::new(0.0, 0.0, -1.0));
+        let rec = tri.hit(&front, &any_t()).unwrap();
+        assert_eq!(rec.t, 2.0);
+        assert!(rec.front_face);
+        assert_close(rec.normal, Vector3::new(0.0, 0.0, 1.0));
+        assert_close(rec.p, Point3::new(0.25, 0.25, 0.0));
+
+        let back = Ray::new(Point3::new(0.25, 0.25, -2.0), Vector3::new(0.0, 0.0, 1.0));
+        let rec = tri.hit(&back, &any_t()).unwrap();
+        assert_eq!(rec.t, 2.0);
+        assert!(!rec.front_face);
+        assert_close(rec.normal, Vector3::new(0.0, 0.0, -1.0));
+
+        let outside = Ray::new(Point3::new(0.75, 0.75, 2.0), Vector3::new(0.0, 0.0, -1.0));
+        assert!(tri.hit(&outside, &any_t()).is_none());
+        let behind = Ray::new(Point3::new(0.25, 0.25, 2.0), Vector3::new(0.0, 0.0, 1.0));
+        assert!(tri.hit(&behind, &any_t()).is_none());
+    }
+
+    #[test]
+    fn shared_edge_hits_exactly_one_triangle() {
+        let [a, b, c, d] = [
+            Point3::new(0.0, 0.0, 0.0),
+            Point3::new(1.0, 0.0, 0.0),
+            Point3::new(1.0, 1.0, 0.0),
+            Point3::new(0.0, 1.0, 0.0),
+        ];
+        let lower = Triangle::new(a, b, c, light());
+        let upper = Triangle::new(a, c, d, light());
+        let upper_flipped = Triangle::new(a, d, c, light());
+
+        let count = |r: &Ray, tris: [&Triangle; 2]| {
+            tris.iter()
+                .filter(|tri| tri.hit(r, &any_t()).is_some())
+                .count()
+        };
+
+        for x in [0.1, 0.25, 0.5, 0.7, 0.9] {
+            let on_edge = Point3::new(x, x, 0.0);
+            for direction in [
+                Vector3::new(0.0, 0.0, -1.0),
+                Vector3::new(0.3, 0.2, -1.0),
+                Vector3::new(-0.7, 0.1, -0.4),
+                Vector3::new(0.2, -0.5, 1.0),
+            ] {
+                let r = Ray::new(on_edge - 3.0 * direction, direction);
+                assert_eq!(count(&r, [&lower, &upper]), 1, "{:?}", r);
+            }
+
+            // Exactly on the edge, winding does not matter.
+            let down = Vector3::new(0.0, 0.0, -1.0);
+            let r = Ray::new(on_edge - down, down);
+            assert_eq!(count(&r, [&lower, &upper_flipped]), 1);
+        }
+    }
+
+    #[test]
+    fn interpolates_uvs_and_normals() {
+        let tri = Triangle::new(
+            Point3::new(0.0, 0.0, 0.0),
+            Point3::new(2.0, 0.0, 0.0),
+            Point3::new(0.0, 3.0, 0.0),
+            light(),
+        )
+        .with_uvs([(0.0, 0.0), (1.0, 0.0), (0.0, 1.0)])
+        .with_normals([
+            Vector3::new(0.0, 0.0, 1.0),
+            Vector3::new(1.0, 0.0, 1.0),
+            Vector3::new(0.0, 1.0, 1.0),
+        ]);
+
+        // Barycentric coordinates (0.5, 0.25, 0.25).
+        let r = Ray::new(Point3::new(0.5, 0.75, 1.0), Vector3::new(0.0, 0.0, -1.0));
+        let rec = tri.hit(&r, &any_t()).unwrap();
+        assert!((rec.u - 0.25).abs() < 1e-6 && (rec.v - 0.25).abs() < 1e-6);
+
+        let s = 0.5f32.sqrt();
+        let expected = 0.5 * Vector3::new(0.0, 0.0, 1.0)
+            + 0.25 * Vector3::new(s, 0.0, s)
+            + 0.25 * Vector3::new(0.0, s, s);
+        assert_close(rec.normal, expected.unit_vector());
+        assert_close(rec.geometric_normal, Vector3::new(0.0, 0.0, 1.0));
+        assert_close(rec.dpdu, Vector3::new(2.0, 0.0, 0.0));
+        assert_close(rec.dpdv, Vector3::new(0.0, 3.0, 0.0));
+    }
+
+    #[test]
+    fn tangents_follow_the_uv_layout() {
+        let vertices = [
+            Point3::new(0.0, 0.0, 0.0),
+            Point3::new(2.0, 0.0, 0.0),
+            Point3::new(0.0, 3.0, 0.0),
+        ];
+        let normal = Vector3::new(0.0, 0.0, 1.0);
+
+        // u runs along the second edge and v backwards along the first.
+        let uvs = [(0.0, 1.0), (0.0, 0.0), (1.0, 1.0)];
+        let (dpdu, dpdv) = triangle_tangents(&vertices, Some(&uvs), &normal);
+        assert_close(dpdu, Vector3::new(0.0, 3.0, 0.0));
+        assert_close(dpdv, Vector3::new(-2.0, 0.0, 0.0));
+
+        // Degenerate UVs still give a tangent frame.
+        let flat = [(0.5, 0.5); 3];
+        let (dpdu, dpdv) = triangle_tangents(&vertices, Some(&flat), &normal);
+        assert!(dpdu.dot(&normal).abs() < 1e-6 && dpdv.dot(&normal).abs() < 1e-6);
+        assert!(dpdu.cross(&dpdv).length() > 0.0);
+    }
+
+    #[test]
+    fn mesh_validates_indices_and_attributes() {
+        let positions = vec![
+            Point3::new(0.0, 0.0, 0.0),
+            Point3::new(1.0, 0.0, 0.0),
+            Point3::new(0.0, 1.0, 0.0),
+        ];
+        let error = |normals, uvs, indices| match TriangleMesh::new(
+            positions.clone(),
+            normals,
+            uvs,
+            indices,
+            light(),
+        ) {
+            Ok(_) => panic!("expected an invalid mesh"),
+            Err(e) => e.to_string(),
+        };
+
+        assert_eq!(
+            error(None, None, vec![[0, 1, 3]]),
+            "mesh index 3 is out of range for 3 vertices"
+        );
+        assert_eq!(
+            error(
+                Some(vec![Vector3::new(0.0, 0.0, 1.0); 2]),
+                None,
+                vec![[0, 1, 2]]
+            ),
+            "mesh has 2 normals for 3 vertices"
+        );
+        assert_eq!(
+            error(None, Some(vec![(0.0, 0.0); 4]), vec![[0, 1, 2]]),
+            "mesh has 4 uvs for 3 vertices"
+        );
+
+        let mesh = TriangleMesh::new(positions, None, None, vec![[0, 1, 2]], light()).unwrap();
+        assert_eq!(mesh.len(), 1);
+        let r = Ray::new(Point3::new(0.2, 0.2, 1.0), Vector3::new(0.0, 0.0, -1.0));
+        assert!(mesh.hit(&r, &any_t()).is_some());
+    }
+
+    #[test]
+    fn mesh_light_sampling_matches_a_quad() {
+        // A 2 by 1 rectangle split into two triangles of equal area.
+        let positions = vec![
+            Point3::new(0.0, 1.0, 0.0),
+            Point3::new(2.0, 1.0, 0.0),
+            Point3::new(2.0, 1.0, 1.0),
+            Point3::new(0.0, 1.0, 1.0),
+        ];
+        let mesh =
+            TriangleMesh::new(positions, None, None, vec![[0, 1, 2], [0, 2, 3]], light()).unwrap();
+        let quad = Quad::new(
+            Point3::new(0.0, 1.0, 0.0),
+            Vector3::new(2.0, 0.0, 0.0),
+            Vector3::new(0.0, 0.0, 1.0),
+            light(),
+        );
+
+        let origin = Point3::new(0.5, 0.0, 0.5);
+        for _ in 0..100 {
+            let direction = mesh.random(&origin);
+            let expected = quad.pdf_value(&origin, &direction);
+            assert!(expected > 0.0);
+            let pdf = mesh.pdf_value(&origin, &direction);
+            assert!(
+                (pdf - expected).abs() < 1e-4 * expected,
+                "{} vs {}",
+                pdf,
+                expected
+            );
+        }
+        assert_eq!(mesh.pdf_value(&origin, &Vector3::new(0.0, -1.0, 0.0)), 0.0);
+    }
+
+    #[test]
+    fn mesh_pdf_counts_every_crossing() {
+        // Two parallel unit triangles, one behind the other.
+        let positions = vec![
+            Point3::new(0.0, 1.0, 0.0),
+            Point3::new(1.0, 1.0, 0.0),
+            Point3::new(0.0, 1.0, 1.0),
+            Point3::new(0.0, 2.0, 0.0),
+            Point3::new(1.0, 2.0, 0.0),
+            Point3::new(0.0, 2.0, 1.0),
+        ];
+        let mesh =
+            TriangleMesh::new(positions, None, None, vec![[0, 1, 2], [3, 4, 5]], light()).unwrap();
+
+        // Straight up through both: d^2 / (cos * total area) for each face.
+        let origin = Point3::new(0.25, 0.0, 0.25);
+        let pdf = mesh.pdf_value(&origin, &Vector3::new(0.0, 1.0, 0.0));
+        assert!((pdf - (1.0 + 4.0)).abs() < 1e-4, "{}", pdf);
+    }
+}
@@ -11,3 +11,13 @@ pub fn linear_to_gamma(linear_component: f32) -> f32 {
         0.0
     }
 }
+
+/// Inverse of [`linear_to_gamma`].
+#[inline(always)]
+pub fn gamma_to_linear(gamma_component: f32) -> f32 {
+    if gamma_component > 0.0 {
+        gamma_component * gamma_component
+    } else {
+        0.0
+    }
+}
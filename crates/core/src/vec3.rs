@@ -1,7 +1,7 @@
 use core::{fmt, ops};
 use std::ops::Neg;
 
-use crate::{interval::Interval, utils::linear_to_gamma};
+use crate::{interval::Interval, rng, utils::linear_to_gamma};
 
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
 pub struct Vector3 {
@@ -47,17 +47,17 @@ impl Vector3 {
 
     pub fn random() -> Self {
         Self::new(
-            rand::random_range(0.0..=1.0),
-            rand::random_range(0.0..=1.0),
-            rand::random_range(0.0..=1.0),
+            rng::random_range(0.0..=1.0),
+            rng::random_range(0.0..=1.0),
+            rng::random_range(0.0..=1.0),
         )
     }
 
     pub fn random_in_range(min: f32, max: f32) -> Self {
         Self::new(
-            rand::random_range(min..=max),
-            rand::random_range(min..=max),
-            rand::random_range(min..=max),
+            rng::random_range(min..=max),
+            rng::random_range(min..=max),
+            rng::random_range(min..=max),
         )
     }
 
@@ -89,6 +89,14 @@ impl Vector3 {
         }
     }
 
+    /// Maps a point of `[0, 1)^2` uniformly onto the unit sphere.
+    pub fn sample_unit_vector(u: (f32, f32)) -> Self {
+        let z = 1.0 - 2.0 * u.0;
+        let r = (1.0 - z * z).max(0.0).sqrt();
+        let phi = 2.0 * core::f32::consts::PI * u.1;
+        Self::new(r * phi.cos(), r * phi.sin(), z)
+    }
+
     pub fn random_on_hemisphere(normal: &Self) -> Self {
         let on_unit_sphere = Self::random_unit_vector();
         if on_unit_sphere.dot(normal) > 0.0 {
@@ -98,6 +106,57 @@ impl Vector3 {
         }
     }
 
+    pub fn random_in_unit_disk() -> Self {
+        loop {
+            let p = Self::new(
+                rng::random_range(-1.0..1.0),
+                rng::random_range(-1.0..1.0),
+                0.0,
+            );
+            if p.length_squared() < 1.0 {
+                return p;
+            }
+        }
+    }
+
+    /// Maps a point of `[0, 1)^2` uniformly onto the unit disk in the XY plane.
+    pub fn sample_in_unit_disk(u: (f32, f32)) -> Self {
+        let r = u.0.sqrt();
+        let phi = 2.0 * core::f32::consts::PI * u.1;
+        Self::new(r * phi.cos(), r * phi.sin(), 0.0)
+    }
+
+    /// Returns a cosine-distributed direction around +Z.
+    pub fn random_cosine_direction() -> Self {
+        Self::sample_cosine_direction((rng::random_range(0.0..1.0), rng::random_range(0.0..1.0)))
+    }
+
+    /// Maps a point of `[0, 1)^2` onto a cosine-distributed direction around +Z.
+    pub fn sample_cosine_direction((r1, r2): (f32, f32)) -> Self {
+        let phi = 2.0 * core::f32::consts::PI * r1;
+        let x = phi.cos() * r2.sqrt();
+        let y = phi.sin() * r2.sqrt();
+        let z = (1.0 - r2).sqrt();
+
+        Self::new(x, y, z)
+    }
+
+    /// Returns two unit vectors that form an orthonormal basis with this unit vector.
+    pub fn coordinate_system(&self) -> (Self, Self) {
+        // Duff et al., "Building an Orthonormal Basis, Revisited".
+        let sign = 1.0f32.copysign(*self.z());
+        let a = -1.0 / (sign + self.z());
+        let b = self.x() * self.y() * a;
+
+        let s = Self::new(
+            1.0 + sign * self.x() * self.x() * a,
+            sign * b,
+            -sign * self.x(),
+        );
+        let t = Self::new(b, sign + self.y() * self.y() * a, -self.y());
+        (s, t)
+    }
+
     #[inline(always)]
     pub const fn near_zero(&self) -> bool {
         const S: f32 = 1e-8;
@@ -296,20 +355,35 @@ impl<T> Wrapper<T> {
     }
 }
 
-impl fmt::Display for Wrapper<&Color> {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let r = self.0.x();
-        let g = self.0.y();
-        let b = self.0.z();
+impl Wrapper<&Color> {
+    /// Gamma-encodes the color and quantizes it to 8 bits per channel.
+    pub fn to_rgb8(&self) -> [u8; 3] {
+        const INTENSITY: Interval = Interval::new(0.000, 0.999);
+        let encode = |c: f32| (256.0 * INTENSITY.clamp(linear_to_gamma(c))) as u8;
+
+        [
+            encode(*self.0.x()),
+            encode(*self.0.y()),
+            encode(*self.0.z()),
+        ]
+    }
 
-        let r = linear_to_gamma(*r);
-        let g = linear_to_gamma(*g);
-        let b = linear_to_gamma(*b);
+    /// Gamma-encodes the color and quantizes it to 16 bits per channel.
+    pub fn to_rgb16(&self) -> [u16; 3] {
+        const INTENSITY: Interval = Interval::new(0.0, 1.0);
+        let encode = |c: f32| (65535.0 * INTENSITY.clamp(linear_to_gamma(c))).round() as u16;
 
-        const INTENSITY: Interval = Interval::new(0.000, 0.999);
-        let rbytes = (256.0 * INTENSITY.clamp(r)) as i32;
-        let gbytes = (256.0 * INTENSITY.clamp(g)) as i32;
-        let bbytes = (256.0 * INTENSITY.clamp(b)) as i32;
+        [
+            encode(*self.0.x()),
+            encode(*self.0.y()),
+            encode(*self.0.z()),
+        ]
+    }
+}
+
+impl fmt::Display for Wrapper<&Color> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let [rbytes, gbytes, bbytes] = self.to_rgb8();
 
         write!(f, "{} {} {}", rbytes, gbytes, bbytes)
     }